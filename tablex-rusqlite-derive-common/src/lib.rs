@@ -0,0 +1,107 @@
+//! 派生宏共用的属性解析, 保证 Table, FromRow 和 Params 选出相同的列
+
+use syn::{Attribute, Expr, ExprLit, Field, ItemStruct, Lit, Meta, Token, punctuated::Punctuated};
+
+/// 字段信息
+pub struct FieldInfo<'a> {
+    pub field: &'a Field,
+    /// 列名, 不是列 (跳过) 时为 `None`
+    pub column_name: Option<String>,
+}
+
+/// 判断每个字段是否为列
+/// - 有`#[table]`或任意`#[column]`时, 每个字段都要标记`#[column]`, 除非有`#[table(all_columns)]`
+/// - 否则全部字段都是列
+/// - `#[column(skip)]`的字段不是列
+/// - 列名为`#[column(name = "...")]`, 不提供时使用字段名
+pub fn column_fields(item_struct: &ItemStruct) -> syn::Result<Vec<FieldInfo<'_>>> {
+    let table_attrs = item_struct
+        .attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("table"))
+        .collect::<Vec<_>>();
+
+    let has_column_attrs = item_struct
+        .fields
+        .iter()
+        .any(|field| field.attrs.iter().any(|attr| attr.path().is_ident("column")));
+
+    let mut all_columns = table_attrs.is_empty() && !has_column_attrs;
+    for attr in table_attrs {
+        all_columns |= has_flag(attr, "all_columns")?;
+    }
+
+    item_struct
+        .fields
+        .iter()
+        .map(|field| {
+            let Some(field_ident) = &field.ident else {
+                return Err(syn::Error::new_spanned(field, "the field must be named"));
+            };
+
+            let column_attrs = field
+                .attrs
+                .iter()
+                .filter(|attr| attr.path().is_ident("column"))
+                .collect::<Vec<_>>();
+
+            // 未标记的字段不能静默地跳过
+            if !all_columns && column_attrs.is_empty() {
+                return Err(syn::Error::new_spanned(
+                    field_ident,
+                    "the field is not marked, add `#[column]` or `#[column(skip)]`, or use `#[table(all_columns)]`",
+                ));
+            }
+
+            let mut is_column = true;
+            let mut column_name = field_ident.to_string();
+            for attr in column_attrs {
+                if has_flag(attr, "skip")? {
+                    is_column = false;
+                }
+                if let Some(name) = string_value(attr, "name")? {
+                    column_name = name;
+                }
+            }
+
+            Ok(FieldInfo {
+                field,
+                column_name: is_column.then_some(column_name),
+            })
+        })
+        .collect()
+}
+
+/// 解析属性中的配置项, 其他配置项由 Table 派生检查
+pub fn parse_metas(attr: &Attribute) -> syn::Result<Punctuated<Meta, Token![,]>> {
+    if let Meta::Path(_) = attr.meta {
+        return Ok(Punctuated::new());
+    }
+
+    attr.parse_args_with(Punctuated::<Meta, Token![,]>::parse_terminated)
+}
+
+/// 属性中是否有 `flag`
+pub fn has_flag(attr: &Attribute, flag: &str) -> syn::Result<bool> {
+    Ok(parse_metas(attr)?
+        .iter()
+        .any(|meta| matches!(meta, Meta::Path(path) if path.is_ident(flag))))
+}
+
+/// 属性中 `key = "value"` 的值
+pub fn string_value(attr: &Attribute, key: &str) -> syn::Result<Option<String>> {
+    for meta in parse_metas(attr)? {
+        if let Meta::NameValue(name_value) = meta
+            && name_value.path.is_ident(key)
+        {
+            return match &name_value.value {
+                Expr::Lit(ExprLit {
+                    lit: Lit::Str(value),
+                    ..
+                }) => Ok(Some(value.value())),
+                value => Err(syn::Error::new_spanned(value, "expected a string literal")),
+            };
+        }
+    }
+    Ok(None)
+}
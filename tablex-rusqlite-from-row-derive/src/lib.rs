@@ -1,31 +1,93 @@
 use proc_macro::TokenStream;
 use quote::quote;
 use syn::{ItemStruct, parse_macro_input};
+use tablex_rusqlite_derive_common::{column_fields, has_flag};
 
-#[proc_macro_derive(FromRow)]
+#[proc_macro_derive(FromRow, attributes(table, column, from_row))]
 pub fn derive_from_row(input: TokenStream) -> TokenStream {
     let item_struct @ ItemStruct { .. } = parse_macro_input!(input);
 
+    match expand_from_row(&item_struct) {
+        Ok(output) => output.into(),
+        Err(err) => err.to_compile_error().into(),
+    }
+}
+
+fn expand_from_row(item_struct: &ItemStruct) -> syn::Result<proc_macro2::TokenStream> {
     let struct_ident = &item_struct.ident;
 
-    let getters = item_struct.fields.iter().enumerate().map(|(index, field)| {
-        let field_name = field.ident.as_ref().unwrap();
+    let fields = column_fields(item_struct)?;
+
+    // `#[from_row(by_name)]` 按列名读取, 否则按顺序读取
+    let mut by_name = false;
+    for attr in item_struct.attrs.iter().filter(|attr| attr.path().is_ident("from_row")) {
+        by_name |= has_flag(attr, "by_name")?;
+    }
+
+    // 跳过的字段使用默认值, 其余字段按顺序 (或按 indices 中的位置) 读取
+    let mut index = 0_usize;
+    let getters = fields.iter().map(|info| {
+        let field_name = info.field.ident.as_ref().unwrap();
 
-        quote! {
-            #field_name: row.get(#index)?
+        if info.column_name.is_none() {
+            return quote! {
+                #field_name: ::std::default::Default::default()
+            };
         }
-    });
 
-    quote! {
+        let getter = if by_name {
+            quote! {
+                #field_name: row.get(indices[#index])?
+            }
+        } else {
+            quote! {
+                #field_name: row.get(#index)?
+            }
+        };
+        index += 1;
+        getter
+    }).collect::<Vec<_>>();
+
+    if !by_name {
+        return Ok(quote! {
+            impl ::tablex_rusqlite::FromRow for #struct_ident {
+                fn from_row(row: &::rusqlite::Row) -> ::rusqlite::Result<Self>
+                where Self: Sized
+                {
+                    Ok(Self {
+                        #(#getters),*
+                    })
+                }
+            }
+        });
+    }
+
+    let column_names = fields.iter().filter_map(|info| info.column_name.as_ref());
+
+    Ok(quote! {
         impl ::tablex_rusqlite::FromRow for #struct_ident {
             fn from_row(row: &::rusqlite::Row) -> ::rusqlite::Result<Self>
             where Self: Sized
+            {
+                let indices = <Self as ::tablex_rusqlite::FromRow>::column_indices(row.as_ref())?;
+                <Self as ::tablex_rusqlite::FromRow>::from_row_with(row, &indices)
+            }
+
+            fn column_indices(stmt: &::rusqlite::Statement) -> ::rusqlite::Result<::std::vec::Vec<usize>>
+            where Self: Sized
+            {
+                Ok(::std::vec![
+                    #(stmt.column_index(#column_names)?),*
+                ])
+            }
+
+            fn from_row_with(row: &::rusqlite::Row, indices: &[usize]) -> ::rusqlite::Result<Self>
+            where Self: Sized
             {
                 Ok(Self {
                     #(#getters),*
                 })
             }
         }
-    }
-    .into()
+    })
 }
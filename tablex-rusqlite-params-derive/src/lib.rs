@@ -1,20 +1,26 @@
 use proc_macro::TokenStream;
 use quote::quote;
-use syn::{parse_macro_input, ItemStruct};
+use syn::{Field, ItemStruct, parse_macro_input};
+use tablex_rusqlite_derive_common::{column_fields, has_flag, parse_metas, string_value};
 
-#[proc_macro_derive(Params)]
+#[proc_macro_derive(Params, attributes(table, column, param))]
 pub fn derive_params(input: TokenStream) -> TokenStream { 
     let item_struct @ ItemStruct { .. } = parse_macro_input!(input);
 
     let struct_ident = &item_struct.ident;
 
-    let params = item_struct
-        .fields
+    let params = match param_names(&item_struct) {
+        Ok(params) => params,
+        Err(err) => return err.to_compile_error().into(),
+    };
+
+    // 跳过的字段不作为参数
+    let params = params
         .iter()
-        .map(|field| {
+        .map(|(field, name)| {
             let field_name = &field.ident;
 
-            let key = format!(":{}", field_name.as_ref().unwrap());
+            let key = format!(":{}", name);
 
             quote! {
                 (#key, &self.#field_name as &dyn ::rusqlite::ToSql)
@@ -35,4 +41,41 @@ pub fn derive_params(input: TokenStream) -> TokenStream {
             }
         }
     }.into()
-}
\ No newline at end of file
+}
+
+/// 参数字段和参数名 (不含`:`)
+/// - `#[param(skip)]`的字段不是参数
+/// - 参数名为`#[param(rename = "...")]`, 不提供时使用字段名
+fn param_names(item_struct: &ItemStruct) -> syn::Result<Vec<(&Field, String)>> {
+    let mut params = Vec::new();
+    for info in column_fields(item_struct)? {
+        if info.column_name.is_none() {
+            continue;
+        }
+        let field = info.field;
+
+        let mut name = field.ident.as_ref().unwrap().to_string();
+        let mut skip = false;
+        for attr in field.attrs.iter().filter(|attr| attr.path().is_ident("param")) {
+            // `param`属性只有 skip 和 rename 两项
+            for meta in parse_metas(attr)? {
+                if !meta.path().is_ident("skip") && !meta.path().is_ident("rename") {
+                    return Err(syn::Error::new_spanned(
+                        meta.path(),
+                        "expected `skip` or `rename = \"...\"`",
+                    ));
+                }
+            }
+
+            skip |= has_flag(attr, "skip")?;
+            if let Some(rename) = string_value(attr, "rename")? {
+                name = rename;
+            }
+        }
+
+        if !skip {
+            params.push((field, name));
+        }
+    }
+    Ok(params)
+}
@@ -1,15 +1,72 @@
 use std::collections::HashSet;
 
-use darling::{FromDeriveInput, FromField};
+use darling::{
+    FromDeriveInput, FromField, FromMeta,
+    util::{Flag, PathList, SpannedValue},
+};
 use proc_macro::TokenStream;
-use quote::{format_ident, quote};
-use syn::{parse_macro_input, Ident, ItemStruct, Path, Type};
+use proc_macro2::Span;
+use quote::{format_ident, quote, quote_spanned};
+use syn::{
+    parse_macro_input, spanned::Spanned, GenericArgument, Ident, ItemStruct, Lit, LitStr, Path,
+    PathArguments, Type,
+};
+use tablex_rusqlite_derive_common::{column_fields, parse_metas};
 
 #[derive(Default, FromDeriveInput)]
 #[darling(default, attributes(table), supports(struct_named))]
 struct TableOptions {
     /// 表名 (如果未指定则使用结构体名)
     name: Option<String>,
+    /// 未标记`column`属性的字段也作为列
+    all_columns: bool,
+    /// 索引 `index(name = "...", columns(a, b), unique)`
+    #[darling(multiple, rename = "index")]
+    indexes: Vec<SpannedValue<IndexOptions>>,
+    /// 复合主键 `primary_key(a, b)`
+    primary_key: Option<ColumnList>,
+    /// 唯一约束 `unique(a, b)`
+    #[darling(multiple)]
+    unique: Vec<ColumnList>,
+    /// `STRICT` 表, 列类型必须实现 `StrictSqlType` (只有内置类型实现)
+    strict: Flag,
+    /// `WITHOUT ROWID` 表, 必须有主键
+    without_rowid: Flag,
+}
+
+/// 表属性中的列列表, 如 `primary_key(a, b)`
+struct ColumnList {
+    /// 属性名的位置 (用于报错)
+    span: Span,
+    fields: PathList,
+}
+
+impl FromMeta for ColumnList {
+    fn from_meta(item: &syn::Meta) -> darling::Result<Self> {
+        Ok(ColumnList {
+            span: item.path().span(),
+            fields: PathList::from_meta(item)?,
+        })
+    }
+}
+
+#[derive(FromMeta)]
+struct IndexOptions {
+    /// 索引名, 不提供时为 `idx_表名_列名...`
+    #[darling(default)]
+    name: Option<String>,
+    /// 索引的列 (字段名)
+    columns: PathList,
+    /// 是否为唯一索引
+    unique: Flag,
+}
+
+/// 索引信息
+struct IndexInfo {
+    name: String,
+    /// 列的字段名
+    fields: Vec<Ident>,
+    is_unique: bool,
 }
 
 #[derive(Default, FromField)]
@@ -33,12 +90,41 @@ struct ColumnOptions {
     // 引用键 (引用到另一张表的某个字段)
     #[darling(default)]
     reference_key: Option<Ident>,
+    /// 删除被引用行时的动作, 如 `on_delete = "cascade"`
+    #[darling(default)]
+    on_delete: Option<LitStr>,
+    /// 更新被引用行时的动作
+    #[darling(default)]
+    on_update: Option<LitStr>,
+    /// 引用在提交时检查 (`DEFERRABLE INITIALLY DEFERRED`)
+    deferrable: Flag,
+    /// 默认值, 字符串为 SQL 表达式 (如 `"CURRENT_TIMESTAMP"`), 也可以是数字或布尔值
+    #[darling(default)]
+    default: Option<Lit>,
+    /// 检查约束的 SQL 表达式, 如 `check = "age >= 0"`
+    #[darling(default)]
+    check: Option<LitStr>,
+    /// 排序规则, 如 `collate = "NOCASE"`
+    #[darling(default)]
+    collate: Option<LitStr>,
+    /// 生成列的 SQL 表达式, 如 `generated = "price * qty"`
+    #[darling(default)]
+    generated: Option<LitStr>,
+    /// 生成列存储在表中 (`STORED`), 否则为 `VIRTUAL`
+    stored: Flag,
+    /// 不作为列 (由 `column_fields` 判断, 这里只接受该配置项)
+    #[darling(rename = "skip")]
+    _skip: Flag,
+    /// 为该列创建索引
+    index: Flag,
 }
 
 /// 列信息 (struct中的字段信息)
 struct ColumnInfo {
     /// 字段名 
     field_name: String,
+    /// 字段位置 (用于报错)
+    span: Span,
     /// 类型信息
     ty: Type,
     /// 列配置
@@ -68,44 +154,60 @@ fn build_column_fn_name<S: AsRef<str>>(field_name: S) -> Ident {
 pub fn derive_table(input: TokenStream) -> TokenStream {
     let item_struct @ ItemStruct { .. } = parse_macro_input!(input);
 
+    match expand_table(item_struct) {
+        Ok(output) => output.into(),
+        Err(err) => err.write_errors().into(),
+    }
+}
+
+fn expand_table(item_struct: ItemStruct) -> darling::Result<proc_macro2::TokenStream> {
     let struct_ident = item_struct.ident.clone();
-    let fields = item_struct.fields.clone();
 
-    // 获取表配置
-    let table_options = TableOptions::from_derive_input(&item_struct.into())
-        .unwrap();
+    // 获取表配置 (同时检查是否为具名字段的结构体)
+    let table_options = TableOptions::from_derive_input(&item_struct.clone().into())?;
 
     // 表名
-    let table_ident = match table_options.name {
-        Some(name) => format_ident!("{}", name),
-        None => struct_ident.clone(),
+    let table_name = match &table_options.name {
+        Some(name) => name.clone(),
+        None => struct_ident.to_string(),
     };
 
-    // 获取所有标记了`column`属性的字段
+    // 获取所有列字段: 与 FromRow 和 Params 派生使用相同的规则
+    let fields = column_fields(&item_struct)?;
     let mut all_errors = darling::Error::accumulator();
     let column_fields = fields
-        .iter()
-        .filter_map(|field| {
-            // 忽略未标记的字段
-            if field.attrs.is_empty() {
-                return None;
+        .into_iter()
+        .filter_map(|info| {
+            let field = info.field;
+            // 跳过的字段也检查`column`属性
+            let column_options = all_errors.handle(ColumnOptions::from_field(field))?;
+            // 跳过的字段不是列
+            info.column_name.as_ref()?;
+
+            // 插入和更新语句按`:字段名`绑定每个列的参数, 列字段不能改参数名或跳过参数
+            for attr in field.attrs.iter().filter(|attr| attr.path().is_ident("param")) {
+                let metas = all_errors.handle(parse_metas(attr).map_err(Into::into))?;
+                for meta in &metas {
+                    if meta.path().is_ident("rename") {
+                        all_errors.push(error_at(
+                            meta.path().span(),
+                            "`#[param(rename)]` can not be used on a column, use `#[column(name = \"...\")]` to rename the column",
+                        ));
+                    } else if meta.path().is_ident("skip") {
+                        all_errors.push(error_at(
+                            meta.path().span(),
+                            "`#[param(skip)]` can not be used on a column, use `#[column(skip)]` if the field is not a column",
+                        ));
+                    }
+                }
             }
 
-            let column_options = ColumnOptions::from_field(field)
-                .map_err(|err| {
-                    // 记录错误
-                    all_errors.push(err.clone());
-                    err
-                })
-                // 忽略出错的字段 
-                .ok()?;
+            // 具名字段已经由 TableOptions 检查
+            let field_ident = field.ident.as_ref()?;
 
             let column_info = ColumnInfo {
-                // 不能提供未命名字段
-                field_name: field.ident
-                    .as_ref()
-                    .map(|ident| ident.to_string())
-                    .expect("Field must be named"),
+                field_name: field_ident.to_string(),
+                span: field_ident.span(),
                 ty: field.ty.clone(),
                 options: column_options,
             };
@@ -113,25 +215,41 @@ pub fn derive_table(input: TokenStream) -> TokenStream {
             Some(column_info)
         })
         .collect::<Vec<_>>();
-    all_errors.finish().unwrap();
-
-    // 检查列名唯一性
-    let mut column_names = HashSet::new();
-    for field in &column_fields {
-        let column_name = field.get_column_name();
+    all_errors.finish()?;
 
-        let inserted = column_names.insert(column_name);
-        if !inserted {
-            panic!("{} is duplicated", column_name)
-        }
-        
+    let mut all_errors = darling::Error::accumulator();
+    if column_fields.is_empty() {
+        all_errors.push(
+            darling::Error::custom(
+                "the table has no columns, mark fields with `#[column]` or use `#[table(all_columns)]`",
+            )
+            .with_span(&struct_ident),
+        );
     }
 
+    let primary_key = build_primary_key(&table_options, &column_fields, &mut all_errors);
+    let unique_constraints = build_unique_constraints(&table_options, &mut all_errors);
+    check_columns(&column_fields, &primary_key, &mut all_errors);
+    check_table_options(&table_options, &column_fields, &primary_key, &mut all_errors);
+    let indexes = build_indexes(&table_name, &table_options, &column_fields, &mut all_errors);
+    all_errors.finish()?;
+
     // 生成列定义函数 column_*
-    let column_def_fns = gen_column_def_fns(&struct_ident, &column_fields);
+    let column_def_fns = gen_column_def_fns(&struct_ident, &column_fields, &primary_key);
 
     // 生成表信息定义
-    let table_info_def = gen_table_info_def(&struct_ident, &table_ident, &column_fields);
+    let extra_table_info = gen_extra_table_info(
+        &struct_ident,
+        &table_options,
+        &indexes,
+        &primary_key,
+        &unique_constraints,
+    );
+
+    // STRICT 表的列类型检查
+    let strict_type_check = gen_strict_type_check(&table_options, &column_fields);
+    let table_info_def =
+        gen_table_info_def(&struct_ident, &table_name, &column_fields, extra_table_info);
 
     // 生成列 value_ref
     let column_refs = column_fields
@@ -175,7 +293,7 @@ pub fn derive_table(input: TokenStream) -> TokenStream {
 
     let output = quote! {
         impl ::tablex_rusqlite::tablex::Table for #struct_ident{
-            type ExtraTableInfo = ();
+            type ExtraTableInfo = ::tablex_rusqlite::meta::SqlExtraTableInfo;
 
             type ExtraColumnInfo = ::tablex_rusqlite::meta::SqlExtraColumnInfo;
 
@@ -209,49 +327,286 @@ pub fn derive_table(input: TokenStream) -> TokenStream {
                 #column_def_fns
             )*
         }
+
+        #strict_type_check
+    };
+
+    Ok(output)
+}
+
+/// 检查列配置, 错误指向出错的字段或属性
+fn check_columns(
+    column_fields: &[ColumnInfo],
+    primary_key: &[Ident],
+    all_errors: &mut darling::error::Accumulator,
+) {
+    // 检查列名唯一性
+    let mut column_names = HashSet::new();
+    for field in column_fields {
+        let column_name = field.get_column_name();
+
+        let inserted = column_names.insert(column_name);
+        if !inserted {
+            all_errors.push(error_at(
+                field.span,
+                format!("column `{}` is duplicated", column_name),
+            ));
+        }
+    }
+
+    // 最多只能有一个自增列
+    let auto_increment_fields = column_fields
+        .iter()
+        .filter(|field| field.options.is_auto_increment);
+    for field in auto_increment_fields.skip(1) {
+        all_errors.push(error_at(
+            field.span,
+            "only one column can be `auto_increment`",
+        ));
+    }
+
+    for field in column_fields {
+        let options = &field.options;
+
+        // 自增列必须是整数类型的唯一主键列
+        if options.is_auto_increment {
+            if !primary_key.iter().any(|key| *key == field.field_name) {
+                all_errors.push(error_at(
+                    field.span,
+                    "an `auto_increment` column must also be `primary`",
+                ));
+            } else if primary_key.len() > 1 {
+                all_errors.push(error_at(
+                    field.span,
+                    "an `auto_increment` column must be the only primary key column",
+                ));
+            }
+            if !is_integer_type(&field.ty) {
+                all_errors.push(
+                    darling::Error::custom(
+                        "an `auto_increment` column must be an integer, e.g. `i64` or `Option<u32>`",
+                    )
+                    .with_span(&field.ty),
+                );
+            }
+        }
+
+        // 引用表和引用键必须同时存在或同时不存在
+        match (&options.reference_table, &options.reference_key) {
+            (Some(table_type), None) => all_errors.push(
+                darling::Error::custom("`reference_table` requires a `reference_key`")
+                    .with_span(table_type),
+            ),
+            (None, Some(key)) => all_errors.push(
+                darling::Error::custom("`reference_key` requires a `reference_table`")
+                    .with_span(key),
+            ),
+            _ => {}
+        }
+
+        // 引用动作只能用于引用列
+        for action in [&options.on_delete, &options.on_update].into_iter().flatten() {
+            if options.reference_table.is_none() {
+                all_errors.push(
+                    darling::Error::custom("a foreign key action requires a `reference_table`")
+                        .with_span(action),
+                );
+            } else if foreign_key_action(action).is_none() {
+                all_errors.push(
+                    darling::Error::custom(
+                        "expected one of `cascade`, `set_null`, `set_default`, `restrict` or `no_action`",
+                    )
+                    .with_span(action),
+                );
+            }
+        }
+        if options.deferrable.is_present() && options.reference_table.is_none() {
+            all_errors.push(error_at(
+                options.deferrable.span(),
+                "`deferrable` requires a `reference_table`",
+            ));
+        }
+
+        // 生成列不能有默认值, 也不能是主键
+        match &options.generated {
+            Some(generated) => {
+                if options.default.is_some() {
+                    all_errors.push(
+                        darling::Error::custom("a `generated` column can not have a `default`")
+                            .with_span(generated),
+                    );
+                }
+                if primary_key.iter().any(|key| *key == field.field_name) {
+                    all_errors.push(
+                        darling::Error::custom("a `generated` column can not be in the primary key")
+                            .with_span(generated),
+                    );
+                }
+            }
+            None if options.stored.is_present() => all_errors.push(error_at(
+                options.stored.span(),
+                "`stored` requires a `generated` expression",
+            )),
+            None => {}
+        }
+
+        if let Some(default) = &options.default
+            && default_sql(default).is_none()
+        {
+            all_errors.push(
+                darling::Error::custom(
+                    "expected a string of a SQL expression, a number or a boolean",
+                )
+                .with_span(default),
+            );
+        }
+    }
+}
+
+/// 默认值的 SQL 表达式, 布尔值为 `1` 或 `0`
+fn default_sql(default: &Lit) -> Option<String> {
+    match default {
+        Lit::Str(value) => Some(value.value()),
+        Lit::Int(value) => Some(value.base10_digits().to_string()),
+        Lit::Float(value) => Some(value.base10_digits().to_string()),
+        Lit::Bool(value) => Some(if value.value { "1" } else { "0" }.to_string()),
+        _ => None,
+    }
+}
+
+/// 检查表配置
+fn check_table_options(
+    table_options: &TableOptions,
+    column_fields: &[ColumnInfo],
+    primary_key: &[Ident],
+    all_errors: &mut darling::error::Accumulator,
+) {
+    let without_rowid = &table_options.without_rowid;
+    if !without_rowid.is_present() {
+        return;
+    }
+
+    if primary_key.is_empty() {
+        all_errors.push(error_at(
+            without_rowid.span(),
+            "a `without_rowid` table requires a primary key",
+        ));
+    }
+
+    // WITHOUT ROWID 表不支持 AUTOINCREMENT
+    for field in column_fields {
+        if field.options.is_auto_increment {
+            all_errors.push(error_at(
+                field.span,
+                "an `auto_increment` column can not be in a `without_rowid` table",
+            ));
+        }
+    }
+}
+
+/// 引用动作对应的 `ForeignKeyAction`, 如 `"set_null"` 或 `"SET NULL"` 为 `SetNull`
+fn foreign_key_action(action: &LitStr) -> Option<Ident> {
+    let variant = match action.value().to_ascii_lowercase().replace(' ', "_").as_str() {
+        "no_action" => "NoAction",
+        "restrict" => "Restrict",
+        "set_null" => "SetNull",
+        "set_default" => "SetDefault",
+        "cascade" => "Cascade",
+        _ => return None,
     };
+    Some(Ident::new(variant, action.span()))
+}
 
-    output.into()
+fn error_at(span: Span, message: impl std::fmt::Display) -> darling::Error {
+    syn::Error::new(span, message).into()
+}
+
+/// 按类型名检查是否为整数 (或整数的 `Option`)
+/// 宏中无法解析类型别名
+fn is_integer_type(ty: &Type) -> bool {
+    const INTEGERS: [&str; 8] = ["i8", "u8", "i16", "u16", "i32", "u32", "i64", "u64"];
+
+    let Type::Path(type_path) = ty else {
+        return false;
+    };
+    let Some(segment) = type_path.path.segments.last() else {
+        return false;
+    };
+
+    if segment.ident == "Option" {
+        return match &segment.arguments {
+            PathArguments::AngleBracketed(arguments) => matches!(
+                arguments.args.first(),
+                Some(GenericArgument::Type(ty)) if is_integer_type(ty)
+            ),
+            _ => false,
+        };
+    }
+
+    INTEGERS.iter().any(|integer| segment.ident == integer)
 }
 
 /// 生成列定义函数 column_*
-fn gen_column_def_fns(struct_ident: &Ident, column_fields: &[ColumnInfo]) -> Vec<proc_macro2::TokenStream> {
+fn gen_column_def_fns(
+    struct_ident: &Ident,
+    column_fields: &[ColumnInfo],
+    primary_key: &[Ident],
+) -> Vec<proc_macro2::TokenStream> {
     column_fields
         .iter()
         .map(|info| {
             let field_name = format_ident!("{}", info.field_name);
-            let column_name = format_ident!("{}", info.get_column_name());
+            let column_name = info.get_column_name();
 
             let ty = info.ty.clone();
-            let is_primary = info.options.is_primary;
+            let is_primary = primary_key.iter().any(|key| *key == info.field_name);
+
+            // 默认值已经由 check_columns 检查
+            let default = optional_str(info.options.default.as_ref().and_then(default_sql));
+            let check = optional_str(info.options.check.as_ref().map(LitStr::value));
+            let collate = optional_str(info.options.collate.as_ref().map(LitStr::value));
+            let generated = optional_str(info.options.generated.as_ref().map(LitStr::value));
+            let is_stored = info.options.stored.is_present();
             let is_auto_increment = info.options.is_auto_increment;
             let is_unique = info.options.is_unique;
 
             let fn_name = info.get_column_fn_name();
 
-            // 检查引用表和引用键必须同时存在或同时不存在
+            // 引用表和引用键已经由 check_columns 检查
             // 宏中无法检查不能引用自身 (引用自身会导致死循环)
             let reference = match (&info.options.reference_table, &info.options.reference_key) {
                 // 生成引用表和键的代码
+                // 函数名使用引用键的位置, 引用键不存在时错误指向 reference_key
                 (Some(table_type), Some(key)) => 
                 {
-                    let column_fn_name = build_column_fn_name(key.to_string());
+                    let column_fn_name = format_ident!("column_{}", key, span = key.span());
+                    // 引用动作已经由 check_columns 检查
+                    let actions = [&info.options.on_delete, &info.options.on_update].map(|action| {
+                        match action.as_ref().and_then(foreign_key_action) {
+                            Some(variant) => quote! { Some(::tablex_rusqlite::meta::ForeignKeyAction::#variant) },
+                            None => quote! { None },
+                        }
+                    });
+                    let [on_delete, on_update] = actions;
+                    let is_deferrable = info.options.deferrable.is_present();
                     quote!{
                         Some(::tablex_rusqlite::meta::Reference{
                             table: < #table_type as ::tablex_rusqlite::tablex::Table > :: table_info(),
-                            column: #table_type :: #column_fn_name()
+                            column: #table_type :: #column_fn_name(),
+                            on_delete: #on_delete,
+                            on_update: #on_update,
+                            is_deferrable: #is_deferrable,
                         })
                     }
                 },
-                (None, None) => quote! { None },
-                _ => panic!("Reference table and key must both be specified or both omitted"),                
+                _ => quote! { None },
             };
 
             quote! {
                 pub fn #fn_name() -> &'static ::tablex_rusqlite::meta::SqlColumnInfo {
                     static COLUMN: ::std::sync::LazyLock<::tablex_rusqlite::meta::SqlColumnInfo> = ::std::sync::LazyLock::new(
                         || ::tablex_rusqlite::meta::SqlColumnInfo {
-                            column_name: stringify!(#column_name),
+                            column_name: #column_name,
                             field_name: stringify!(#field_name),
                             offset: std::mem::offset_of!(#struct_ident, #field_name),
                             size: std::mem::size_of::<#ty>(),
@@ -262,6 +617,11 @@ fn gen_column_def_fns(struct_ident: &Ident, column_fields: &[ColumnInfo]) -> Vec
                                 is_auto_increment: #is_auto_increment,
                                 is_not_null: !<#ty as ::tablex_rusqlite::SqlType>::is_nullable(),
                                 reference: #reference,
+                                default: #default,
+                                check: #check,
+                                collate: #collate,
+                                generated: #generated,
+                                is_stored: #is_stored,
                             }
                         }
                     );
@@ -273,8 +633,20 @@ fn gen_column_def_fns(struct_ident: &Ident, column_fields: &[ColumnInfo]) -> Vec
         .collect::<Vec<_>>()
 }
 
+fn optional_str(value: Option<String>) -> proc_macro2::TokenStream {
+    match value {
+        Some(value) => quote! { Some(#value) },
+        None => quote! { None },
+    }
+}
+
 /// 生成表信息
-fn gen_table_info_def(struct_ident: &Ident, table_ident: &Ident, column_fields: &[ColumnInfo]) -> proc_macro2::TokenStream {
+fn gen_table_info_def(
+    struct_ident: &Ident,
+    table_name: &str,
+    column_fields: &[ColumnInfo],
+    extra_table_info: proc_macro2::TokenStream,
+) -> proc_macro2::TokenStream {
 
     // 生成列信息函数的调用
     let columns = column_fields
@@ -297,9 +669,9 @@ fn gen_table_info_def(struct_ident: &Ident, table_ident: &Ident, column_fields:
 
             static TABLE_INFO : ::std::sync::LazyLock< ::tablex_rusqlite::meta::SqlTableInfo> = ::std::sync::LazyLock::new(||
                 ::tablex_rusqlite::meta::SqlTableInfo {
-                    table_name: stringify!(#table_ident),
+                    table_name: #table_name,
                     columns: &*COLUMNS,
-                    extra: ()
+                    extra: #extra_table_info
                 }
             );
 
@@ -307,3 +679,219 @@ fn gen_table_info_def(struct_ident: &Ident, table_ident: &Ident, column_fields:
         }
     }
 }
+
+/// 汇总列上和表上声明的索引
+fn build_indexes(
+    table_name: &str,
+    table_options: &TableOptions,
+    column_fields: &[ColumnInfo],
+    all_errors: &mut darling::error::Accumulator,
+) -> Vec<IndexInfo> {
+    let mut indexes = column_fields
+        .iter()
+        .filter(|field| field.options.index.is_present())
+        .map(|field| IndexInfo {
+            name: format!("idx_{}_{}", table_name, field.get_column_name()),
+            fields: vec![Ident::new(&field.field_name, field.options.index.span())],
+            is_unique: false,
+        })
+        .collect::<Vec<_>>();
+
+    for options in &table_options.indexes {
+        if options.columns.is_empty() {
+            all_errors.push(error_at(
+                options.span(),
+                "an index requires at least one column in `columns(...)`",
+            ));
+            continue;
+        }
+
+        let fields = field_idents(&options.columns, all_errors);
+
+        let name = options.name.clone().unwrap_or_else(|| {
+            let column_names = fields
+                .iter()
+                .map(|field| {
+                    column_fields
+                        .iter()
+                        .find(|info| *field == info.field_name)
+                        .map(|info| info.get_column_name().to_string())
+                        .unwrap_or_else(|| field.to_string())
+                })
+                .collect::<Vec<_>>();
+            format!("idx_{}_{}", table_name, column_names.join("_"))
+        });
+
+        indexes.push(IndexInfo {
+            name,
+            fields,
+            is_unique: options.unique.is_present(),
+        });
+    }
+
+    // 索引名在数据库中必须唯一
+    let mut index_names = HashSet::new();
+    for index in &indexes {
+        if !index_names.insert(index.name.as_str()) {
+            let span = index.fields.first().map(|field| field.span()).unwrap_or_else(Span::call_site);
+            all_errors.push(error_at(span, format!("index `{}` is duplicated", index.name)));
+        }
+    }
+
+    indexes
+}
+
+/// 主键列的字段名
+/// - `#[table(primary_key(a, b))]` 与 `#[column(primary)]` 不能同时使用
+/// - 多个 `#[column(primary)]` 按字段顺序组成复合主键
+fn build_primary_key(
+    table_options: &TableOptions,
+    column_fields: &[ColumnInfo],
+    all_errors: &mut darling::error::Accumulator,
+) -> Vec<Ident> {
+    let primary_fields = column_fields
+        .iter()
+        .filter(|field| field.options.is_primary);
+
+    let Some(primary_key) = &table_options.primary_key else {
+        return primary_fields
+            .map(|field| Ident::new(&field.field_name, field.span))
+            .collect();
+    };
+
+    for field in primary_fields {
+        all_errors.push(error_at(
+            field.span,
+            "`primary` can not be used together with `#[table(primary_key(...))]`",
+        ));
+    }
+
+    if primary_key.fields.is_empty() {
+        all_errors.push(error_at(
+            primary_key.span,
+            "a primary key requires at least one column",
+        ));
+    }
+
+    field_idents(&primary_key.fields, all_errors)
+}
+
+/// 表上声明的唯一约束的字段名
+fn build_unique_constraints(
+    table_options: &TableOptions,
+    all_errors: &mut darling::error::Accumulator,
+) -> Vec<Vec<Ident>> {
+    table_options
+        .unique
+        .iter()
+        .filter_map(|columns| {
+            if columns.fields.is_empty() {
+                all_errors.push(error_at(
+                    columns.span,
+                    "a unique constraint requires at least one column",
+                ));
+                return None;
+            }
+            Some(field_idents(&columns.fields, all_errors))
+        })
+        .collect()
+}
+
+/// 表属性中的列以字段名给出
+fn field_idents(paths: &PathList, all_errors: &mut darling::error::Accumulator) -> Vec<Ident> {
+    paths
+        .iter()
+        .filter_map(|path| {
+            let ident = path.get_ident().cloned();
+            if ident.is_none() {
+                all_errors.push(darling::Error::custom("expected a field name").with_span(path));
+            }
+            ident
+        })
+        .collect()
+}
+
+/// 生成字段对应的列信息函数的调用
+/// 函数名使用字段的位置, 字段不是列时错误指向该字段名
+fn gen_column_calls(struct_ident: &Ident, fields: &[Ident]) -> Vec<proc_macro2::TokenStream> {
+    fields
+        .iter()
+        .map(|field| {
+            let column_fn_name = format_ident!("column_{}", field, span = field.span());
+            quote! { #struct_ident :: #column_fn_name() }
+        })
+        .collect()
+}
+
+/// 生成表的额外信息
+fn gen_extra_table_info(
+    struct_ident: &Ident,
+    table_options: &TableOptions,
+    indexes: &[IndexInfo],
+    primary_key: &[Ident],
+    unique_constraints: &[Vec<Ident>],
+) -> proc_macro2::TokenStream {
+    let indexes = indexes
+        .iter()
+        .map(|index| {
+            let name = &index.name;
+            let is_unique = index.is_unique;
+            let columns = gen_column_calls(struct_ident, &index.fields);
+
+            quote! {
+                ::tablex_rusqlite::meta::Index {
+                    name: #name,
+                    columns: ::std::vec![#(#columns),*],
+                    is_unique: #is_unique,
+                }
+            }
+        })
+        .collect::<Vec<_>>();
+
+    let primary_key = gen_column_calls(struct_ident, primary_key);
+
+    let is_strict = table_options.strict.is_present();
+    let is_without_rowid = table_options.without_rowid.is_present();
+
+    let unique_constraints = unique_constraints.iter().map(|fields| {
+        let columns = gen_column_calls(struct_ident, fields);
+        quote! { ::std::vec![#(#columns),*] }
+    });
+
+    quote! {
+        ::tablex_rusqlite::meta::SqlExtraTableInfo {
+            primary_key: ::std::vec![#(#primary_key),*],
+            unique_constraints: ::std::vec![#(#unique_constraints),*],
+            indexes: ::std::vec![#(#indexes),*],
+            is_strict: #is_strict,
+            is_without_rowid: #is_without_rowid,
+        }
+    }
+}
+
+/// 生成 STRICT 表的列类型检查, 错误指向不满足的字段类型
+fn gen_strict_type_check(
+    table_options: &TableOptions,
+    column_fields: &[ColumnInfo],
+) -> proc_macro2::TokenStream {
+    if !table_options.strict.is_present() {
+        return quote! {};
+    }
+
+    let checks = column_fields.iter().map(|info| {
+        let ty = &info.ty;
+        quote_spanned! { ty.span() =>
+            assert_strict_sql_type::<#ty>();
+        }
+    });
+
+    quote! {
+        const _: () = {
+            fn assert_strict_sql_type<T: ::tablex_rusqlite::StrictSqlType>() {}
+
+            fn assert_strict_columns() {
+                #(#checks)*
+            }
+        };
+    }
+}
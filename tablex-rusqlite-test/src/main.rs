@@ -1,5 +1,7 @@
+use rusqlite::TransactionBehavior;
 use tablex_rusqlite::{
-    Builder, ConnectionExt, Executor, FromRow, Params, Table,
+    Builder, BuilderMut, ColumnExt, Conflict, ConnectionExt, Error, ExecuteWith, Executor, FromRow,
+    Order, Params, SqlTable, Table, TransactionOptions,
     sql::{self},
     tablex::Table,
 };
@@ -19,6 +21,30 @@ struct UserInfo {
     address: Option<String>,
 }
 
+/// 按列名读取 user_info 的部分列
+#[derive(Debug, FromRow)]
+#[from_row(by_name)]
+struct UserAge {
+    #[column]
+    id: u32,
+    #[column(name = "user_name")]
+    name: String,
+    #[column]
+    age: u32,
+}
+
+#[derive(Debug, FromRow)]
+struct UserName {
+    id: u32,
+    name: String,
+}
+
+#[derive(Debug, Params)]
+struct AgeRange {
+    min_age: u32,
+    max_age: u32,
+}
+
 #[derive(Debug, Table)]
 #[table(name = "tx")]
 struct Transaction {
@@ -30,6 +56,114 @@ struct Transaction {
     amount: f64,
 }
 
+/// 参数名与字段名不同, 跳过的字段不是参数
+#[derive(Debug, Params)]
+struct UserNameFilter {
+    #[param(rename = "user_name")]
+    name: String,
+    #[param(skip)]
+    expected: usize,
+}
+
+/// 全部字段都是列, 除了标记了 `skip` 的字段
+#[derive(Debug, Table, FromRow, Params)]
+#[table(name = "tag", all_columns, index(columns(label), unique))]
+struct Tag {
+    #[column(primary, auto_increment)]
+    id: i64,
+    /// 只有文档注释的字段也是列
+    label: String,
+    #[column(skip)]
+    usage_count: u32,
+}
+
+/// 没有任何属性时, 三个派生都把全部字段作为列
+#[derive(Debug, PartialEq, Table, FromRow, Params)]
+struct Counter {
+    name: String,
+    hits: i64,
+}
+
+/// 访问记录, 按时间查询
+#[derive(Debug, Table, FromRow, Params)]
+#[table(name = "visit", all_columns)]
+struct Visit {
+    #[column(primary, auto_increment)]
+    id: i64,
+    #[column(index)]
+    visited_at: i64,
+}
+
+/// 删除标签时一并删除链接
+#[derive(Debug, Table, FromRow, Params)]
+#[table(name = "tag_link", all_columns)]
+struct TagLink {
+    #[column(primary, auto_increment)]
+    id: i64,
+    #[column(reference_table = Tag, reference_key = id, on_delete = "cascade", deferrable)]
+    tag_id: i64,
+    url: String,
+}
+
+/// 带默认值, 检查约束和排序规则的列
+#[derive(Debug, Table, FromRow, Params)]
+#[table(name = "note", all_columns)]
+struct Note {
+    #[column(primary, auto_increment)]
+    id: i64,
+    #[column(default = "CURRENT_TIMESTAMP")]
+    created_at: String,
+    #[column(default = 1, check = "priority BETWEEN 0 AND 9")]
+    priority: i64,
+    #[column(collate = "NOCASE")]
+    text: String,
+}
+
+/// 键值表, 值的类型由 SQLite 检查
+#[derive(Debug, Table, FromRow, Params)]
+#[table(name = "setting", all_columns, strict, without_rowid)]
+struct Setting {
+    #[column(primary = true)]
+    key: String,
+    value: Option<String>,
+    /// ANY 列保留值原来的类型
+    extra: Option<rusqlite::types::Value>,
+}
+
+/// 总价由 SQLite 计算, 插入和更新时不发送
+#[derive(Debug, Table, FromRow, Params)]
+#[table(name = "line_item", all_columns)]
+struct LineItem {
+    #[column(primary, auto_increment)]
+    id: i64,
+    price: f64,
+    qty: i64,
+    #[column(generated = "price * qty", stored, index)]
+    total: f64,
+}
+
+/// 复合主键的关联表, 同一分组中的位置唯一
+#[derive(Debug, Table, FromRow, Params)]
+#[table(
+    name = "user_group",
+    all_columns,
+    primary_key(user_id, group_id),
+    unique(group_id, position)
+)]
+struct UserGroup {
+    user_id: u32,
+    group_id: u32,
+    position: u32,
+}
+
+/// 只有主键列的关联表
+#[derive(Debug, Table, Params)]
+#[table(name = "follow", all_columns, primary_key(follower_id, followee_id))]
+struct Follow {
+    follower_id: u32,
+    followee_id: u32,
+}
+
 fn main() {
     let age_column = UserInfo::column_age();
 
@@ -71,7 +205,18 @@ fn main() {
     let insert_user_info = sql::insert_row().with_auto_increment();
     let mut insert_user_info_1 = insert_user_info.build(&connection, &user).unwrap();
     println!("Insert row SQL 1: {}", insert_user_info_1.sql());
-    insert_user_info_1.execute().unwrap();
+    let inserted = insert_user_info_1.execute().unwrap();
+    println!("Insert row 1 result: {:?}", inserted);
+    assert_eq!(inserted.last_insert_rowid, 1);
+
+    // 冲突被忽略时没有插入任何行
+    let ignored = sql::insert_row()
+        .or(Conflict::Ignore)
+        .build(&connection, &UserInfo { id: 1, ..user })
+        .unwrap()
+        .execute()
+        .unwrap();
+    assert_eq!(ignored.rows_affected, 0);
 
     let user_info2 = UserInfo {
         id: 0,
@@ -90,6 +235,17 @@ fn main() {
     let returned_user_info = insert_user_info_2.execute().unwrap();
     println!("Returned user info: {:?}", returned_user_info);
 
+    let updated_user_info = UserInfo {
+        age: 60,
+        ..returned_user_info
+    };
+    let mut update_user_info = sql::update_row()
+        .build(&connection, &updated_user_info)
+        .unwrap();
+    println!("Update row SQL: {}", update_user_info.sql());
+    let updated = update_user_info.execute().unwrap();
+    assert_eq!(updated.rows_affected, 1);
+
     let mut select_rows = sql::select_rows::<UserInfo>()
         .filter_raw("age > 40")
         .build(&connection, &())
@@ -98,6 +254,732 @@ fn main() {
     let user_infos = select_rows.execute().unwrap();
     println!("Selected user infos: {:?}", user_infos);
 
+    // 位置参数绑定到 filter_raw 的占位符
+    let mut select_rows_with_params = sql::select_rows::<UserInfo>()
+        .filter_raw("age > ?1")
+        .build(&connection, &(40,))
+        .unwrap();
+    println!("Select rows with positional params SQL: {}", select_rows_with_params.sql());
+    assert_eq!(select_rows_with_params.execute().unwrap().len(), user_infos.len());
+
+    // 参数不能绑定到过滤表达式的占位符
+    let err = sql::select_rows::<UserInfo>()
+        .filter(UserInfo::column_age().eq(1))
+        .build(&connection, &(3,))
+        .err()
+        .unwrap();
+    println!("Unused param error: {}", err);
+    assert!(matches!(err, Error::Misuse(_)));
+
+    let upsert_user_info = UserInfo {
+        id: 2,
+        name: "Bob".to_string(),
+        age: 61,
+        address: None,
+    };
+    let mut upsert_user_info = sql::insert_row()
+        .on_conflict(UserInfo::column_id())
+        .do_update((UserInfo::column_age(), UserInfo::column_address()))
+        .returning_row()
+        .build(&connection, &upsert_user_info)
+        .unwrap();
+    println!("Upsert row SQL: {}", upsert_user_info.sql());
+    let upserted_user_info = upsert_user_info.execute().unwrap();
+    assert_eq!(upserted_user_info.age, 61);
+    assert_eq!(upserted_user_info.address, None);
+
+    // 冲突时跳过插入, 没有返回的行
+    let existing_user_info = UserInfo {
+        id: 2,
+        name: "Bob".to_string(),
+        age: 61,
+        address: None,
+    };
+    let ignored = sql::insert_row()
+        .on_conflict(UserInfo::column_id())
+        .do_nothing()
+        .build(&connection, &existing_user_info)
+        .unwrap()
+        .execute()
+        .unwrap();
+    assert_eq!(ignored.rows_affected, 0);
+    let err = sql::insert_row()
+        .on_conflict(UserInfo::column_id())
+        .do_nothing()
+        .returning_row()
+        .build(&connection, &existing_user_info)
+        .err()
+        .unwrap();
+    println!("Returning skipped row error: {}", err);
+    assert!(matches!(err, Error::Misuse(_)));
+    assert!(matches!(
+        sql::insert_row()
+            .or(Conflict::Ignore)
+            .returning_row()
+            .build(&connection, &existing_user_info),
+        Err(Error::Misuse(_))
+    ));
+
+    let mut select_filtered = sql::select_rows::<UserInfo>()
+        .filter(
+            UserInfo::column_age()
+                .gt(40)
+                .and(UserInfo::column_name().eq("Bob")),
+        )
+        .build(&connection, &())
+        .unwrap();
+    println!("Select rows with filter SQL: {}", select_filtered.sql());
+    assert_eq!(select_filtered.execute().unwrap().len(), 1);
+
+    let mut select_age_range = sql::select_rows::<UserInfo>()
+        .filter_raw("age BETWEEN :min_age AND :max_age")
+        .build(
+            &connection,
+            &AgeRange {
+                min_age: 50,
+                max_age: 70,
+            },
+        )
+        .unwrap();
+    println!("Select rows with params SQL: {}", select_age_range.sql());
+    assert_eq!(select_age_range.execute().unwrap().len(), 1);
+
+    // 列必须属于查询的表
+    assert!(matches!(
+        sql::select_rows::<UserInfo>()
+            .filter(Transaction::column_amount().gt(1.0))
+            .build(&connection, &()),
+        Err(Error::UnknownColumn { table: "user_info", .. })
+    ));
+
+    // 解码失败时带上表、列和 SQL
+    let err = sql::select_rows::<UserInfo>()
+        .columns::<(u32, u32), _>((UserInfo::column_id(), UserInfo::column_name()))
+        .build(&connection, &())
+        .unwrap()
+        .execute()
+        .unwrap_err();
+    println!("Decode error: {}", err);
+    match err {
+        Error::Sqlite {
+            table, column, sql, ..
+        } => {
+            assert_eq!(table, Some("user_info"));
+            assert_eq!(column.map(|column| column.column_name), Some("user_name"));
+            assert_eq!(sql.as_deref(), Some("SELECT id, user_name FROM user_info"));
+        }
+        err => panic!("unexpected error: {}", err),
+    }
+
+    let user_info3 = UserInfo {
+        id: 0,
+        name: "Carol".to_string(),
+        age: 18,
+        address: None,
+    };
+    let user_info3 = sql::insert_row()
+        .with_auto_increment()
+        .returning_row()
+        .build(&connection, &user_info3)
+        .unwrap()
+        .execute()
+        .unwrap();
+    let mut delete_user_info = sql::delete_rows()
+        .by_primary_key(&user_info3)
+        .build(&connection, &())
+        .unwrap();
+    println!("Delete rows SQL: {}", delete_user_info.sql());
+    assert_eq!(delete_user_info.execute().unwrap().rows_affected, 1);
+
+    let mut delete_returning = sql::delete_rows::<UserInfo>()
+        .filter_raw("age < 0")
+        .returning_rows()
+        .build(&connection, &())
+        .unwrap();
+    println!("Delete rows And Returning rows SQL: {}", delete_returning.sql());
+    assert!(delete_returning.execute().unwrap().is_empty());
+
+    let user_infos = [("Dave", 25), ("Eve", 25), ("Frank", 33)].map(|(name, age)| UserInfo {
+        id: 0,
+        name: name.to_string(),
+        age,
+        address: None,
+    });
+    let inserted = sql::insert_row()
+        .with_auto_increment()
+        .insert_rows(&connection, &user_infos)
+        .unwrap();
+    assert_eq!(inserted, 3);
+
+    // 任意一行失败时整批回滚
+    let duplicated_user_infos = [("Heidi", 20), ("Ivan", 21)].map(|(name, age)| UserInfo {
+        id: 100,
+        name: name.to_string(),
+        age,
+        address: None,
+    });
+    assert!(
+        sql::insert_row()
+            .insert_iter(&connection, duplicated_user_infos.iter())
+            .is_err()
+    );
+    // SQLite 自己回滚整批时, 仍然返回约束错误
+    let err = sql::insert_row()
+        .or(Conflict::Rollback)
+        .insert_rows(&connection, &duplicated_user_infos)
+        .unwrap_err();
+    println!("Batch rollback error: {}", err);
+    assert_eq!(err.sqlite_error_code(), Some(rusqlite::ErrorCode::ConstraintViolation));
+    assert!(
+        sql::find::<UserInfo>()
+            .build(&connection, &(100,))
+            .unwrap()
+            .execute()
+            .unwrap()
+            .is_none()
+    );
+
+    let mut cursor = None;
+    let mut paged_user_infos = Vec::new();
+    loop {
+        let mut select_page = sql::select_rows::<UserInfo>()
+            .order_by(UserInfo::column_age(), Order::Desc)
+            .order_by(UserInfo::column_id(), Order::Asc);
+        if let Some(cursor) = cursor {
+            select_page = select_page.after_cursor(cursor);
+        }
+        let mut select_page = select_page.page(2).build(&connection, &()).unwrap();
+        println!("Select page SQL: {}", select_page.sql());
+        let page = select_page.execute().unwrap();
+        paged_user_infos.extend(page.rows);
+        cursor = page.next;
+        if cursor.is_none() {
+            break;
+        }
+    }
+    println!("Paged user infos: {:?}", paged_user_infos);
+    assert_eq!(paged_user_infos.len(), 5);
+
+    let mut select_after = sql::select_rows::<UserInfo>()
+        .order_by(UserInfo::column_age(), Order::Desc)
+        .order_by(UserInfo::column_id(), Order::Asc)
+        .after(&paged_user_infos[2])
+        .limit(10)
+        .build(&connection, &())
+        .unwrap();
+    println!("Select after row SQL: {}", select_after.sql());
+    assert_eq!(select_after.execute().unwrap().len(), 2);
+    assert!(matches!(select_after.execute_with(&(0,)), Err(Error::Misuse(_))));
+
+    // 重新执行时只绑定 filter_raw 的占位符, 保留 after 的值
+    let mut select_after_age = sql::select_rows::<UserInfo>()
+        .filter_raw("age > ?1")
+        .order_by(UserInfo::column_age(), Order::Desc)
+        .order_by(UserInfo::column_id(), Order::Asc)
+        .after(&paged_user_infos[2])
+        .build(&connection, &(100,))
+        .unwrap();
+    assert!(select_after_age.execute().unwrap().is_empty());
+    assert_eq!(select_after_age.execute_with(&(0,)).unwrap().len(), 2);
+
+    // 排序列的值在构建时读取, `after` 可以在 `order_by` 之前调用
+    let mut select_after_first = sql::select_rows::<UserInfo>()
+        .after(&paged_user_infos[2])
+        .order_by(UserInfo::column_age(), Order::Desc)
+        .order_by(UserInfo::column_id(), Order::Asc)
+        .build(&connection, &())
+        .unwrap();
+    assert_eq!(select_after_first.execute().unwrap().len(), 2);
+
+    // 排序列的值为 NULL 时无法继续分页
+    let err = sql::select_rows::<UserInfo>()
+        .order_by(UserInfo::column_address(), Order::Asc)
+        .after(&UserInfo {
+            id: 1,
+            name: "Alice".to_string(),
+            age: 40,
+            address: None,
+        })
+        .build(&connection, &())
+        .err()
+        .unwrap();
+    println!("Keyset after NULL error: {}", err);
+    assert!(matches!(err, Error::Misuse(_)));
+
+    let mut select_columns = sql::select_rows::<UserInfo>()
+        .columns::<(u32, String), _>((UserInfo::column_id(), UserInfo::column_name()))
+        .filter(UserInfo::column_age().lt(30))
+        .order_by(UserInfo::column_id(), Order::Asc)
+        .build(&connection, &())
+        .unwrap();
+    println!("Select columns SQL: {}", select_columns.sql());
+    println!("Selected columns: {:?}", select_columns.execute().unwrap());
+
+    let user_names: Vec<UserName> = sql::select_rows::<UserInfo>()
+        .columns((UserInfo::column_id(), UserInfo::column_name()))
+        .build(&connection, &())
+        .unwrap()
+        .execute()
+        .unwrap();
+    println!("Selected user names: {:?}", user_names);
+    assert_eq!((user_names[0].id, user_names[0].name.as_str()), (1, "Alice"));
+
+    let mut select_iter = sql::select_rows::<UserInfo>()
+        .order_by(UserInfo::column_id(), Order::Asc)
+        .build(&connection, &())
+        .unwrap();
+    let mut total_age = 0;
+    select_iter
+        .for_each(|user_info| {
+            total_age += user_info.age;
+            Ok(())
+        })
+        .unwrap();
+    println!("Total age: {}", total_age);
+    assert_eq!(select_iter.first().unwrap().name, "Alice");
+
+    let mut select_missing = sql::select_rows::<UserInfo>()
+        .filter(UserInfo::column_name().eq("Nobody"))
+        .build(&connection, &())
+        .unwrap();
+    assert!(select_missing.optional().unwrap().is_none());
+
+    let mut name_count = 0;
+    connection
+        .query_raw_for_each::<_, String, _>("SELECT user_name FROM user_info", &(), |_| {
+            name_count += 1;
+            Ok(())
+        })
+        .unwrap();
+    assert_eq!(name_count, 5);
+
+    let mut user_info7 = UserInfo {
+        id: 0,
+        name: "Grace".to_string(),
+        age: 45,
+        address: None,
+    };
+    let mut insert_write_back = sql::insert_row()
+        .with_auto_increment()
+        .build_mut(&connection, &mut user_info7)
+        .unwrap();
+    println!("Insert row and write back SQL: {}", insert_write_back.sql());
+    insert_write_back.execute().unwrap();
+    println!("Written back user info: {:?}", user_info7);
+    assert_eq!(user_info7.id, 7);
+    sql::delete_rows()
+        .by_primary_key(&user_info7)
+        .build(&connection, &())
+        .unwrap()
+        .execute()
+        .unwrap();
+
+    let mut find_user_info = sql::find::<UserInfo>().build(&connection, &(2,)).unwrap();
+    println!("Find row SQL: {}", find_user_info.sql());
+    assert_eq!(find_user_info.execute().unwrap().unwrap().name, "Bob");
+    let missing_user_info = sql::find::<UserInfo>()
+        .build(&connection, &(100,))
+        .unwrap()
+        .execute()
+        .unwrap();
+    assert!(missing_user_info.is_none());
+
+    // 预编译一次, 使用不同的参数多次执行
+    let mut find_prepared = sql::find::<UserInfo>().build(&connection, &(1,)).unwrap();
+    let found_names = [1, 2, 100].map(|id| {
+        find_prepared
+            .execute_with(&(id,))
+            .unwrap()
+            .map(|user_info| user_info.name)
+    });
+    println!("Found names: {:?}", found_names);
+    assert_eq!(
+        found_names,
+        [Some("Alice".to_string()), Some("Bob".to_string()), None]
+    );
+
+    // 外层事务提交, 内层保存点回滚
+    let committed = connection
+        .transaction_with(
+            &TransactionOptions::default().behavior(TransactionBehavior::Immediate),
+            |tx| {
+                let mut user_info = UserInfo {
+                    id: 0,
+                    name: "Judy".to_string(),
+                    age: 28,
+                    address: None,
+                };
+                sql::insert_row()
+                    .with_auto_increment()
+                    .build_mut(tx, &mut user_info)?
+                    .execute()?;
+
+                let nested = tx.transaction(|tx| {
+                    sql::delete_rows()
+                        .by_primary_key(&user_info)
+                        .build(tx, &())?
+                        .execute()?;
+                    Err::<(), _>(rusqlite::Error::QueryReturnedNoRows.into())
+                });
+                assert!(nested.is_err());
+
+                Ok(user_info)
+            },
+        )
+        .unwrap();
+    let found = sql::find::<UserInfo>()
+        .build(&connection, &(committed.id,))
+        .unwrap()
+        .execute()
+        .unwrap();
+    assert_eq!(found.unwrap().name, "Judy");
+    sql::delete_rows()
+        .by_primary_key(&committed)
+        .build(&connection, &())
+        .unwrap()
+        .execute()
+        .unwrap();
+
+    // 跳过的字段不是列, 读取时使用默认值
+    assert_eq!(Tag::table_info().columns.len(), 2);
+    sql::create_table::<Tag>()
+        .build(&connection, &())
+        .unwrap()
+        .execute()
+        .unwrap();
+    let mut tag = Tag {
+        id: 0,
+        label: "rust".to_string(),
+        usage_count: 3,
+    };
+    sql::insert_row()
+        .with_auto_increment()
+        .build_mut(&connection, &mut tag)
+        .unwrap()
+        .execute()
+        .unwrap();
+    let tags = sql::select_rows::<Tag>()
+        .build(&connection, &())
+        .unwrap()
+        .execute()
+        .unwrap();
+    println!("Selected tags: {:?}", tags);
+
+    assert_eq!(Counter::table_info().table_name, "Counter");
+    assert_eq!(Counter::table_info().columns.len(), 2);
+    sql::create_table::<Counter>()
+        .build(&connection, &())
+        .unwrap()
+        .execute()
+        .unwrap();
+    let counter = Counter {
+        name: "visits".to_string(),
+        hits: 7,
+    };
+    sql::insert_row()
+        .build(&connection, &counter)
+        .unwrap()
+        .execute()
+        .unwrap();
+    let counters = sql::select_rows::<Counter>()
+        .build(&connection, &())
+        .unwrap()
+        .execute()
+        .unwrap();
+    assert_eq!(counters, vec![counter]);
+
+    // 索引随表一起创建
+    sql::create_table::<Visit>()
+        .build(&connection, &())
+        .unwrap()
+        .execute()
+        .unwrap();
+    let indexes = connection
+        .query_raw::<_, (String,)>(
+            "SELECT name FROM sqlite_master WHERE type = 'index' AND name LIKE 'idx_%' ORDER BY name",
+            &(),
+        )
+        .unwrap();
+    assert_eq!(
+        indexes,
+        vec![("idx_tag_label".to_string(),), ("idx_visit_visited_at".to_string(),)]
+    );
+    let err = sql::insert_row()
+        .with_auto_increment()
+        .build(&connection, &tag)
+        .unwrap()
+        .execute()
+        .unwrap_err();
+    println!("Unique index error: {}", err);
+    assert_eq!(err.sqlite_error_code(), Some(rusqlite::ErrorCode::ConstraintViolation));
+
+    assert_eq!(tags[0].id, tag.id);
+    assert_eq!(tags[0].label, "rust");
+    assert_eq!(tags[0].usage_count, 0);
+
+    // 按列名读取, 与查询的列顺序无关
+    let reordered = connection
+        .query_raw::<_, UserAge>(
+            "SELECT age, user_name, id FROM user_info WHERE id = ?1",
+            &(2,),
+        )
+        .unwrap();
+    assert_eq!(reordered[0].id, 2);
+    assert_eq!(reordered[0].name, "Bob");
+    assert_eq!(reordered[0].age, 61);
+
+    // 缺少列时报告列名
+    let err = connection
+        .query_raw::<_, UserAge>("SELECT id, age FROM user_info", &())
+        .unwrap_err();
+    println!("Missing column error: {}", err);
+    assert!(matches!(
+        err.sqlite_error(),
+        Some(rusqlite::Error::InvalidColumnName(name)) if name == "user_name"
+    ));
+
+    // 复合主键作为表约束
+    assert_eq!(UserGroup::primary_key_columns().len(), 2);
+    let mut create_user_group = sql::create_table::<UserGroup>()
+        .build(&connection, &())
+        .unwrap();
+    println!("Create table SQL: {}", create_user_group.sql());
+    create_user_group.execute().unwrap();
+    let membership = UserGroup {
+        user_id: 1,
+        group_id: 7,
+        position: 1,
+    };
+    let insert_user_group = sql::insert_row();
+    insert_user_group
+        .insert_rows(
+            &connection,
+            &[
+                UserGroup {
+                    user_id: 1,
+                    group_id: 7,
+                    position: 1,
+                },
+                UserGroup {
+                    user_id: 2,
+                    group_id: 7,
+                    position: 2,
+                },
+            ],
+        )
+        .unwrap();
+    let err = insert_user_group
+        .build(&connection, &UserGroup { position: 3, ..membership })
+        .unwrap()
+        .execute()
+        .unwrap_err();
+    assert_eq!(err.sqlite_error_code(), Some(rusqlite::ErrorCode::ConstraintViolation));
+    let err = insert_user_group
+        .build(&connection, &UserGroup { user_id: 3, ..membership })
+        .unwrap()
+        .execute()
+        .unwrap_err();
+    println!("Unique constraint error: {}", err);
+    assert_eq!(err.sqlite_error_code(), Some(rusqlite::ErrorCode::ConstraintViolation));
+
+    sql::update_row()
+        .build(&connection, &UserGroup { position: 5, ..membership })
+        .unwrap()
+        .execute()
+        .unwrap();
+    let found = sql::find::<UserGroup>()
+        .build(&connection, &(1, 7))
+        .unwrap()
+        .execute()
+        .unwrap();
+    assert_eq!(found.unwrap().position, 5);
+    let deleted = sql::delete_rows()
+        .by_primary_key(&membership)
+        .build(&connection, &())
+        .unwrap()
+        .execute()
+        .unwrap();
+    assert_eq!(deleted.rows_affected, 1);
+
+    // 没有主键以外的列时无法更新
+    let follow = Follow {
+        follower_id: 1,
+        followee_id: 2,
+    };
+    let err = sql::update_row()
+        .build(&connection, &follow)
+        .err()
+        .unwrap();
+    println!("Update all-key table error: {}", err);
+    assert!(matches!(err, Error::Misuse(_)));
+
+    // 开启外键约束后引用才会被检查
+    connection.enable_foreign_keys().unwrap();
+    let mut create_tag_link = sql::create_table::<TagLink>()
+        .build(&connection, &())
+        .unwrap();
+    println!("Create table SQL: {}", create_tag_link.sql());
+    create_tag_link.execute().unwrap();
+    let link = TagLink {
+        id: 0,
+        tag_id: tag.id,
+        url: "https://www.rust-lang.org".to_string(),
+    };
+    sql::insert_row()
+        .with_auto_increment()
+        .build(&connection, &link)
+        .unwrap()
+        .execute()
+        .unwrap();
+    let err = sql::insert_row()
+        .with_auto_increment()
+        .build(&connection, &TagLink { tag_id: -1, ..link })
+        .unwrap()
+        .execute()
+        .unwrap_err();
+    println!("Foreign key error: {}", err);
+    assert_eq!(err.sqlite_error_code(), Some(rusqlite::ErrorCode::ConstraintViolation));
+    sql::delete_rows()
+        .by_primary_key(&tag)
+        .build(&connection, &())
+        .unwrap()
+        .execute()
+        .unwrap();
+    let links = sql::select_rows::<TagLink>()
+        .build(&connection, &())
+        .unwrap()
+        .execute()
+        .unwrap();
+    assert!(links.is_empty());
+
+    // 默认值由 SQLite 填充
+    let mut create_note = sql::create_table::<Note>()
+        .build(&connection, &())
+        .unwrap();
+    println!("Create table SQL: {}", create_note.sql());
+    create_note.execute().unwrap();
+    let note = Note {
+        id: 0,
+        created_at: String::new(),
+        priority: 0,
+        text: "remember the milk".to_string(),
+    };
+    let inserted_note = sql::insert_row()
+        .with_auto_increment()
+        .with_defaults()
+        .returning_row()
+        .build(&connection, &note)
+        .unwrap()
+        .execute()
+        .unwrap();
+    println!("Inserted note: {:?}", inserted_note);
+    assert_eq!(inserted_note.priority, 1);
+    assert!(!inserted_note.created_at.is_empty());
+    let err = sql::insert_row()
+        .with_auto_increment()
+        .build(&connection, &Note { priority: 10, ..note })
+        .unwrap()
+        .execute()
+        .unwrap_err();
+    println!("Check constraint error: {}", err);
+    assert_eq!(err.sqlite_error_code(), Some(rusqlite::ErrorCode::ConstraintViolation));
+
+    // 内容比较不区分大小写
+    let notes = connection
+        .query_raw::<_, Note>("SELECT * FROM note WHERE text = 'REMEMBER THE MILK'", &())
+        .unwrap();
+    assert_eq!(notes.len(), 1);
+
+    // STRICT 表拒绝类型不符的值
+    let mut create_setting = sql::create_table::<Setting>()
+        .build(&connection, &())
+        .unwrap();
+    println!("Create table SQL: {}", create_setting.sql());
+    create_setting.execute().unwrap();
+    let setting = Setting {
+        key: "theme".to_string(),
+        value: Some("dark".to_string()),
+        extra: Some(rusqlite::types::Value::Integer(3)),
+    };
+    sql::insert_row()
+        .build(&connection, &setting)
+        .unwrap()
+        .execute()
+        .unwrap();
+    let found = sql::find::<Setting>()
+        .build(&connection, &("theme",))
+        .unwrap()
+        .execute()
+        .unwrap();
+    let found = found.unwrap();
+    assert_eq!(found.value.as_deref(), Some("dark"));
+    assert_eq!(found.extra, Some(rusqlite::types::Value::Integer(3)));
+    let err = connection
+        .execute("INSERT INTO setting (key, value) VALUES ('size', x'00')", ())
+        .unwrap_err();
+    println!("Strict table error: {}", err);
+    assert_eq!(err.sqlite_error_code(), Some(rusqlite::ErrorCode::ConstraintViolation));
+
+    // 生成列不插入也不更新, 但会被读取
+    let mut create_line_item = sql::create_table::<LineItem>()
+        .build(&connection, &())
+        .unwrap();
+    println!("Create table SQL: {}", create_line_item.sql());
+    create_line_item.execute().unwrap();
+    let mut line_item = sql::insert_row()
+        .with_auto_increment()
+        .returning_row()
+        .build(
+            &connection,
+            &LineItem {
+                id: 0,
+                price: 2.5,
+                qty: 4,
+                total: 0.0,
+            },
+        )
+        .unwrap()
+        .execute()
+        .unwrap();
+    assert_eq!(line_item.total, 10.0);
+    line_item.qty = 2;
+    let mut update_line_item = sql::update_row().build(&connection, &line_item).unwrap();
+    println!("Update row SQL: {}", update_line_item.sql());
+    update_line_item.execute().unwrap();
+    let line_item = sql::find::<LineItem>()
+        .build(&connection, &(line_item.id,))
+        .unwrap()
+        .execute()
+        .unwrap()
+        .unwrap();
+    assert_eq!(line_item.total, 5.0);
+
+    // 生成列不能作为 upsert 的更新列, 更新列也不能为空
+    let err = sql::insert_row()
+        .on_conflict(LineItem::column_id())
+        .do_update(LineItem::column_total())
+        .build(&connection, &line_item)
+        .err()
+        .unwrap();
+    println!("Upsert generated column error: {}", err);
+    assert!(matches!(err, Error::GeneratedColumn { table: "line_item", .. }));
+    let err = sql::insert_row()
+        .on_conflict(LineItem::column_id())
+        .do_update(Vec::new())
+        .build(&connection, &line_item)
+        .err()
+        .unwrap();
+    assert!(matches!(err, Error::Misuse(_)));
+
+    let filter = UserNameFilter {
+        name: "Alice".to_string(),
+        expected: 1,
+    };
+    let filtered = connection
+        .query_raw::<_, UserInfo>("SELECT * FROM user_info WHERE user_name = :user_name", &filter)
+        .unwrap();
+    assert_eq!(filtered.len(), filter.expected);
+
     let results = connection
         .query_raw::<_, (u32, String)>("SELECT id, user_name FROM user_info WHERE user_name='Bob'", &())
         .unwrap();
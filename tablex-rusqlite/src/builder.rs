@@ -7,5 +7,18 @@ pub trait Builder<Params: crate::Params> {
         &self,
         connection: &'connection rusqlite::Connection,
         params: &Params,
-    ) -> rusqlite::Result<Self::Executor<'connection>>;
+    ) -> crate::Result<Self::Executor<'connection>>;
+}
+
+/// A builder whose executor writes back into the params, e.g. the generated key of an insert
+pub trait BuilderMut<Params: crate::Params> {
+    type Executor<'connection, 'params>: Executor
+    where
+        Params: 'params;
+
+    fn build_mut<'connection, 'params>(
+        &self,
+        connection: &'connection rusqlite::Connection,
+        params: &'params mut Params,
+    ) -> crate::Result<Self::Executor<'connection, 'params>>;
 }
@@ -0,0 +1,51 @@
+use crate::meta::SqlColumnInfo;
+
+/// A list of column handles, e.g. `UserInfo::column_id()` or
+/// `(UserInfo::column_id(), UserInfo::column_user_name())`
+pub trait Columns {
+    fn into_columns(self) -> Vec<&'static SqlColumnInfo>;
+}
+
+impl Columns for &'static SqlColumnInfo {
+    fn into_columns(self) -> Vec<&'static SqlColumnInfo> {
+        vec![self]
+    }
+}
+
+impl<const N: usize> Columns for [&'static SqlColumnInfo; N] {
+    fn into_columns(self) -> Vec<&'static SqlColumnInfo> {
+        self.to_vec()
+    }
+}
+
+impl Columns for &[&'static SqlColumnInfo] {
+    fn into_columns(self) -> Vec<&'static SqlColumnInfo> {
+        self.to_vec()
+    }
+}
+
+impl Columns for Vec<&'static SqlColumnInfo> {
+    fn into_columns(self) -> Vec<&'static SqlColumnInfo> {
+        self
+    }
+}
+
+macro_rules! impl_columns_for_tuple {
+    ($($name:ident),+) => {
+        impl Columns for ($(impl_columns_for_tuple!(@column $name),)+) {
+            fn into_columns(self) -> Vec<&'static SqlColumnInfo> {
+                #[allow(non_snake_case)]
+                let ($($name,)+) = self;
+                vec![$($name),+]
+            }
+        }
+    };
+    (@column $name:ident) => { &'static SqlColumnInfo };
+}
+
+impl_columns_for_tuple!(A);
+impl_columns_for_tuple!(A, B);
+impl_columns_for_tuple!(A, B, C);
+impl_columns_for_tuple!(A, B, C, D);
+impl_columns_for_tuple!(A, B, C, D, E);
+impl_columns_for_tuple!(A, B, C, D, E, F);
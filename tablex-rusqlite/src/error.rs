@@ -0,0 +1,224 @@
+use std::{cell::OnceCell, fmt::Display};
+
+use rusqlite::ErrorCode;
+
+use crate::{
+    FromRow,
+    meta::{SqlColumnInfo, SqlTableInfo},
+};
+
+pub type Result<T, E = Error> = std::result::Result<T, E>;
+
+/// The error of the builders and executors
+#[derive(Debug)]
+pub enum Error {
+    /// An error of rusqlite, with the statement it happened in
+    Sqlite {
+        source: rusqlite::Error,
+        /// The SQL text of the statement
+        sql: Option<String>,
+        /// The table the statement was built for
+        table: Option<&'static str>,
+        /// The column of the table which failed, e.g. when decoding a row
+        column: Option<&'static SqlColumnInfo>,
+    },
+    /// The table has no primary key to find, update or delete by
+    MissingPrimaryKey { table: &'static str },
+    /// The column does not belong to the table of the statement
+    UnknownColumn {
+        table: &'static str,
+        column: &'static SqlColumnInfo,
+    },
+    /// The column is generated by SQLite, so it can not be written
+    GeneratedColumn {
+        table: &'static str,
+        column: &'static SqlColumnInfo,
+    },
+    /// The field of the column is not a built-in `SqlType`, so its value can not be read or written
+    UnreadableValue {
+        table: &'static str,
+        column: &'static SqlColumnInfo,
+    },
+    /// The builder is used in a wrong way, e.g. keyset pagination without `order_by`
+    Misuse(String),
+}
+
+impl Error {
+    /// The underlying rusqlite error, if any
+    pub fn sqlite_error(&self) -> Option<&rusqlite::Error> {
+        match self {
+            Error::Sqlite { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+
+    /// The SQLite error code, e.g. `ErrorCode::ConstraintViolation`
+    pub fn sqlite_error_code(&self) -> Option<ErrorCode> {
+        self.sqlite_error()?.sqlite_error_code()
+    }
+
+    /// The SQL text of the statement which failed
+    pub fn sql(&self) -> Option<&str> {
+        match self {
+            Error::Sqlite { sql, .. } => sql.as_deref(),
+            _ => None,
+        }
+    }
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::Sqlite {
+                source,
+                sql,
+                table,
+                column,
+            } => {
+                write!(f, "{}", source)?;
+                if let Some(table) = table {
+                    write!(f, " (table `{}`", table)?;
+                    if let Some(column) = column {
+                        write!(f, ", column `{}`", column.column_name)?;
+                    }
+                    write!(f, ")")?;
+                }
+                if let Some(sql) = sql {
+                    write!(f, " in `{}`", sql)?;
+                }
+                Ok(())
+            }
+            Error::MissingPrimaryKey { table } => {
+                write!(f, "table `{}` has no primary key", table)
+            }
+            Error::UnknownColumn { table, column } => write!(
+                f,
+                "column `{}` does not belong to table `{}`",
+                column.column_name, table
+            ),
+            Error::GeneratedColumn { table, column } => write!(
+                f,
+                "column `{}` of table `{}` is generated and can not be written",
+                column.column_name, table
+            ),
+            Error::UnreadableValue { table, column } => write!(
+                f,
+                "the value of column `{}` of table `{}` can not be read",
+                column.column_name, table
+            ),
+            Error::Misuse(message) => write!(f, "{}", message),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Sqlite { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
+
+impl From<rusqlite::Error> for Error {
+    fn from(source: rusqlite::Error) -> Self {
+        Error::Sqlite {
+            source,
+            sql: None,
+            table: None,
+            column: None,
+        }
+    }
+}
+
+/// The statement an executor runs, attached to the errors it reports
+/// # details
+/// - it decodes one row type, the column indices of the type are cached
+pub(crate) struct SqlContext {
+    sql: String,
+    table: Option<&'static SqlTableInfo>,
+    indices: OnceCell<Vec<usize>>,
+}
+
+impl SqlContext {
+    pub(crate) fn new(sql: String, table: Option<&'static SqlTableInfo>) -> Self {
+        SqlContext {
+            sql,
+            table,
+            indices: OnceCell::new(),
+        }
+    }
+
+    pub(crate) fn sql(&self) -> &str {
+        &self.sql
+    }
+
+    /// Fill the missing statement and table of an error
+    pub(crate) fn error(&self, error: impl Into<Error>) -> Error {
+        match error.into() {
+            Error::Sqlite {
+                source,
+                sql,
+                table,
+                column,
+            } => Error::Sqlite {
+                source,
+                sql: sql.or_else(|| Some(self.sql.clone())),
+                table: table.or(self.table.map(|table| table.table_name)),
+                column,
+            },
+            error => error,
+        }
+    }
+
+    pub(crate) fn prepare<'connection>(
+        &self,
+        connection: &'connection rusqlite::Connection,
+    ) -> Result<rusqlite::Statement<'connection>> {
+        connection.prepare(&self.sql).map_err(|err| self.error(err))
+    }
+
+    /// Decode a row, the column of a failure is mapped to the column of the table
+    pub(crate) fn decode<R>(&self, row: &rusqlite::Row<'_>) -> Result<R>
+    where
+        R: FromRow,
+    {
+        // 列的位置只在第一行查找一次
+        let indices = match self.indices.get() {
+            Some(indices) => Ok(indices),
+            None => {
+                R::column_indices(row.as_ref()).map(|indices| self.indices.get_or_init(|| indices))
+            }
+        };
+
+        indices
+            .and_then(|indices| R::from_row_with(row, indices))
+            .map_err(|source| {
+                let name = match &source {
+                    rusqlite::Error::InvalidColumnIndex(index)
+                    | rusqlite::Error::InvalidColumnType(index, ..)
+                    | rusqlite::Error::FromSqlConversionFailure(index, ..)
+                    | rusqlite::Error::IntegralValueOutOfRange(index, _) => {
+                        row.as_ref().column_name(*index).ok()
+                    }
+                    rusqlite::Error::InvalidColumnName(name) => Some(name.as_str()),
+                    _ => None,
+                };
+                // 按结果列名查找表中的列
+                let column = name.and_then(|name| {
+                    self.table?
+                        .columns
+                        .iter()
+                        .copied()
+                        .find(|column| column.column_name == name)
+                });
+
+                Error::Sqlite {
+                    source,
+                    sql: Some(self.sql.clone()),
+                    table: self.table.map(|table| table.table_name),
+                    column,
+                }
+            })
+    }
+}
@@ -1,7 +1,37 @@
+/// The result of an insert, update or delete
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ExecuteResult {
+    /// The number of inserted, updated or deleted rows
+    pub rows_affected: usize,
+    /// The rowid of the most recent successful insert on the connection
+    /// # details
+    /// - it is not changed by an update, a delete or an ignored insert
+    pub last_insert_rowid: i64,
+}
+
+impl ExecuteResult {
+    pub(crate) fn new(connection: &rusqlite::Connection, rows_affected: usize) -> Self {
+        ExecuteResult {
+            rows_affected,
+            last_insert_rowid: connection.last_insert_rowid(),
+        }
+    }
+}
+
 pub trait Executor {
     type Output;
 
-    fn execute(&mut self) -> rusqlite::Result<Self::Output>;
+    fn execute(&mut self) -> crate::Result<Self::Output>;
 
+    /// The SQL with the bound values expanded, or the SQL text when it can not be expanded
     fn sql(&mut self) -> String;
 }
+
+/// An executor which keeps its prepared statement and runs it again with other params
+/// # details
+/// - only the placeholders of `params` are rebound, the values bound by the builder
+///   (e.g. filter expressions) are kept
+/// - a param on a placeholder of the builder is a `Misuse`, it never replaces the builder's value
+pub trait ExecuteWith<Params: crate::Params>: Executor {
+    fn execute_with(&mut self, params: &Params) -> crate::Result<Self::Output>;
+}
@@ -0,0 +1,219 @@
+use rusqlite::ToSql;
+
+use crate::{Error, SqlTable, meta::SqlColumnInfo};
+
+/// A typed `WHERE` expression built from column handles
+///
+/// Values are never inlined into the SQL, they are bound as parameters named
+/// `:__tablex_1`, `:__tablex_2`, ... in the order they appear.
+/// ```ignore
+/// let expr = UserInfo::column_age()
+///     .gt(40)
+///     .and(UserInfo::column_name().eq("Bob"));
+/// ```
+pub struct Expr {
+    node: Node,
+}
+
+enum Node {
+    Compare {
+        column: &'static SqlColumnInfo,
+        operator: &'static str,
+        value: Box<dyn ToSql>,
+    },
+    Null {
+        column: &'static SqlColumnInfo,
+        negated: bool,
+    },
+    In {
+        column: &'static SqlColumnInfo,
+        values: Vec<Box<dyn ToSql>>,
+        negated: bool,
+    },
+    And(Box<Expr>, Box<Expr>),
+    Or(Box<Expr>, Box<Expr>),
+    Not(Box<Expr>),
+}
+
+impl Expr {
+    pub(crate) fn compare(
+        column: &'static SqlColumnInfo,
+        operator: &'static str,
+        value: Box<dyn ToSql>,
+    ) -> Self {
+        Expr {
+            node: Node::Compare {
+                column,
+                operator,
+                value,
+            },
+        }
+    }
+
+    pub(crate) fn null(column: &'static SqlColumnInfo, negated: bool) -> Self {
+        Expr {
+            node: Node::Null { column, negated },
+        }
+    }
+
+    pub(crate) fn in_list(
+        column: &'static SqlColumnInfo,
+        values: Vec<Box<dyn ToSql>>,
+        negated: bool,
+    ) -> Self {
+        Expr {
+            node: Node::In {
+                column,
+                values,
+                negated,
+            },
+        }
+    }
+
+    pub fn and(self, other: Expr) -> Expr {
+        Expr {
+            node: Node::And(Box::new(self), Box::new(other)),
+        }
+    }
+
+    pub fn or(self, other: Expr) -> Expr {
+        Expr {
+            node: Node::Or(Box::new(self), Box::new(other)),
+        }
+    }
+}
+
+impl std::ops::Not for Expr {
+    type Output = Expr;
+
+    fn not(self) -> Expr {
+        Expr {
+            node: Node::Not(Box::new(self)),
+        }
+    }
+}
+
+impl Expr {
+    fn columns(&self, columns: &mut Vec<&'static SqlColumnInfo>) {
+        match &self.node {
+            Node::Compare { column, .. } | Node::Null { column, .. } | Node::In { column, .. } => {
+                columns.push(column)
+            }
+            Node::And(left, right) | Node::Or(left, right) => {
+                left.columns(columns);
+                right.columns(columns);
+            }
+            Node::Not(expr) => expr.columns(columns),
+        }
+    }
+
+    /// Check that every column of the expression belongs to the table `T`
+    pub(crate) fn check_table<T>(&self) -> crate::Result<()>
+    where
+        T: SqlTable,
+    {
+        let table_info = T::table_info();
+
+        let mut columns = Vec::new();
+        self.columns(&mut columns);
+
+        match columns.iter().find(|column| !table_info.has_column(column)) {
+            Some(column) => Err(Error::UnknownColumn {
+                table: table_info.table_name,
+                column,
+            }),
+            None => Ok(()),
+        }
+    }
+
+    /// Build the SQL of the expression
+    pub(crate) fn build_sql(&self) -> String {
+        let mut sql = String::new();
+        self.write_sql(&mut sql, &mut 0);
+        sql
+    }
+
+    fn write_sql(&self, sql: &mut String, placeholder: &mut usize) {
+        let mut next_placeholder = || {
+            *placeholder += 1;
+            format!(":__tablex_{}", placeholder)
+        };
+
+        match &self.node {
+            Node::Compare {
+                column, operator, ..
+            } => {
+                let placeholder = next_placeholder();
+                sql.push_str(&format!(
+                    "{} {} {}",
+                    column.column_name, operator, placeholder
+                ));
+            }
+            Node::Null { column, negated } => {
+                let not = if *negated { " NOT" } else { "" };
+                sql.push_str(&format!("{} IS{} NULL", column.column_name, not));
+            }
+            Node::In {
+                column,
+                values,
+                negated,
+            } => {
+                let not = if *negated { " NOT" } else { "" };
+                let placeholders = values
+                    .iter()
+                    .map(|_| next_placeholder())
+                    .collect::<Vec<_>>();
+                sql.push_str(&format!(
+                    "{}{} IN ({})",
+                    column.column_name,
+                    not,
+                    placeholders.join(", ")
+                ));
+            }
+            Node::And(left, right) | Node::Or(left, right) => {
+                let operator = match &self.node {
+                    Node::And(..) => "AND",
+                    _ => "OR",
+                };
+                sql.push('(');
+                left.write_sql(sql, placeholder);
+                sql.push_str(&format!(" {} ", operator));
+                right.write_sql(sql, placeholder);
+                sql.push(')');
+            }
+            Node::Not(expr) => {
+                sql.push_str("NOT (");
+                expr.write_sql(sql, placeholder);
+                sql.push(')');
+            }
+        }
+    }
+
+    fn values<'a>(&'a self, values: &mut Vec<&'a dyn ToSql>) {
+        match &self.node {
+            Node::Compare { value, .. } => values.push(value.as_ref()),
+            Node::Null { .. } => {}
+            Node::In {
+                values: in_values, ..
+            } => values.extend(in_values.iter().map(|value| value.as_ref())),
+            Node::And(left, right) | Node::Or(left, right) => {
+                left.values(values);
+                right.values(values);
+            }
+            Node::Not(expr) => expr.values(values),
+        }
+    }
+
+    /// Bind the values of the expression to a statement built from [`Expr::build_sql`]
+    pub(crate) fn bind_values(&self, stmt: &mut rusqlite::Statement<'_>) -> rusqlite::Result<()> {
+        let mut values = Vec::new();
+        self.values(&mut values);
+
+        for (index, value) in values.into_iter().enumerate() {
+            let name = format!(":__tablex_{}", index + 1);
+            stmt.raw_bind_parameter(name.as_str(), value)?;
+        }
+
+        Ok(())
+    }
+}
@@ -1,20 +1,50 @@
-use crate::{bind_params, Builder, Executor, FromRow, Params};
-use rusqlite::{fallible_iterator::FallibleIterator, Connection};
+use crate::{
+    bind_params, error::SqlContext, meta::SqlColumnInfo, transaction, Builder, Executor, Expr,
+    FromRow, Params, TransactionOptions,
+};
+use rusqlite::{Connection, ToSql};
 
 pub trait ConnectionExt {
     fn execute<B, P>(
         &self,
         builder: &B,
         params: &P,
-    ) -> rusqlite::Result<<<B as Builder<P>>::Executor<'_> as Executor>::Output>
+    ) -> crate::Result<<<B as Builder<P>>::Executor<'_> as Executor>::Output>
     where
         B: Builder<P>,
         P: Params;
 
-    fn query_raw<P, R>(&self, sql: &str, params: &P) -> rusqlite::Result<Vec<R>>
+    fn query_raw<P, R>(&self, sql: &str, params: &P) -> crate::Result<Vec<R>>
     where
         P: Params,
         R: FromRow;
+
+    /// Like `query_raw`, but call `f` with every decoded row instead of collecting them
+    fn query_raw_for_each<P, R, F>(&self, sql: &str, params: &P, f: F) -> crate::Result<()>
+    where
+        P: Params,
+        R: FromRow,
+        F: FnMut(R) -> crate::Result<()>;
+
+    /// Run `f` inside a transaction with the default [`TransactionOptions`],
+    /// committed on `Ok` and rolled back on `Err` or panic
+    /// # details
+    /// - a call nested inside another transaction becomes a `SAVEPOINT`
+    /// - `f` is called again when the database is busy, so it should not have other side effects
+    fn transaction<R, F>(&self, f: F) -> crate::Result<R>
+    where
+        F: FnMut(&Connection) -> crate::Result<R>;
+
+    /// Like `transaction`, started and retried according to `options`
+    fn transaction_with<R, F>(&self, options: &TransactionOptions, f: F) -> crate::Result<R>
+    where
+        F: FnMut(&Connection) -> crate::Result<R>;
+
+    /// Enforce the `REFERENCES` of the tables, which SQLite does not by default
+    /// # details
+    /// - it is a setting of the connection, call it once after opening
+    /// - it has no effect inside a transaction
+    fn enable_foreign_keys(&self) -> crate::Result<()>;
 }
 
 impl ConnectionExt for Connection {
@@ -22,7 +52,7 @@ impl ConnectionExt for Connection {
         &self,
         builder: &B,
         params: &P,
-    ) -> rusqlite::Result<<<B as Builder<P>>::Executor<'_> as Executor>::Output>
+    ) -> crate::Result<<<B as Builder<P>>::Executor<'_> as Executor>::Output>
     where
         B: Builder<P>,
         P: Params,
@@ -31,17 +61,153 @@ impl ConnectionExt for Connection {
         executor.execute()
     }
     
-    fn query_raw<P, R>(&self, sql: &str, params: &P) -> rusqlite::Result<Vec<R>>
+    fn query_raw<P, R>(&self, sql: &str, params: &P) -> crate::Result<Vec<R>>
     where
         P: Params,
         R: FromRow {
-        let mut stmt = self.prepare(sql)?;
+        let context = SqlContext::new(sql.to_string(), None);
 
-        bind_params(params.params(), &mut stmt)?;
+        let mut stmt = context.prepare(self)?;
 
-        let rows = stmt.raw_query();
+        bind_params(params.params(), &mut stmt).map_err(|err| context.error(err))?;
 
-        rows.map(|row| R::from_row(row))
+        let rows = stmt.raw_query().and_then(|row| context.decode(row));
+
+        rows.map(|row| row.map_err(|err| context.error(err)))
             .collect()
     }
+
+    fn query_raw_for_each<P, R, F>(&self, sql: &str, params: &P, mut f: F) -> crate::Result<()>
+    where
+        P: Params,
+        R: FromRow,
+        F: FnMut(R) -> crate::Result<()>,
+    {
+        let context = SqlContext::new(sql.to_string(), None);
+
+        let mut stmt = context.prepare(self)?;
+
+        bind_params(params.params(), &mut stmt).map_err(|err| context.error(err))?;
+
+        let mut rows = stmt.raw_query();
+        while let Some(row) = rows.next().map_err(|err| context.error(err))? {
+            f(context.decode(row)?)?;
+        }
+        Ok(())
+    }
+
+    fn transaction<R, F>(&self, f: F) -> crate::Result<R>
+    where
+        F: FnMut(&Connection) -> crate::Result<R>,
+    {
+        transaction::transaction(self, &TransactionOptions::default(), f)
+    }
+
+    fn transaction_with<R, F>(&self, options: &TransactionOptions, f: F) -> crate::Result<R>
+    where
+        F: FnMut(&Connection) -> crate::Result<R>,
+    {
+        transaction::transaction(self, options, f)
+    }
+
+    fn enable_foreign_keys(&self) -> crate::Result<()> {
+        let context = SqlContext::new("PRAGMA foreign_keys = ON".to_string(), None);
+
+        self.execute_batch(context.sql())
+            .map_err(|err| context.error(err))
+    }
+}
+
+/// Build [`Expr`] from the generated column handles, e.g. `UserInfo::column_age().gt(40)`
+pub trait ColumnExt {
+    /// `column = value`
+    fn eq<V: ToSql + 'static>(&self, value: V) -> Expr;
+    /// `column <> value`
+    fn ne<V: ToSql + 'static>(&self, value: V) -> Expr;
+    /// `column > value`
+    fn gt<V: ToSql + 'static>(&self, value: V) -> Expr;
+    /// `column >= value`
+    fn ge<V: ToSql + 'static>(&self, value: V) -> Expr;
+    /// `column < value`
+    fn lt<V: ToSql + 'static>(&self, value: V) -> Expr;
+    /// `column <= value`
+    fn le<V: ToSql + 'static>(&self, value: V) -> Expr;
+    /// `column LIKE pattern`
+    fn like<V: ToSql + 'static>(&self, pattern: V) -> Expr;
+    /// `column IN (values...)`
+    fn is_in<V, I>(&self, values: I) -> Expr
+    where
+        V: ToSql + 'static,
+        I: IntoIterator<Item = V>;
+    /// `column NOT IN (values...)`
+    fn not_in<V, I>(&self, values: I) -> Expr
+    where
+        V: ToSql + 'static,
+        I: IntoIterator<Item = V>;
+    /// `column IS NULL`
+    fn is_null(&self) -> Expr;
+    /// `column IS NOT NULL`
+    fn is_not_null(&self) -> Expr;
+}
+
+impl ColumnExt for &'static SqlColumnInfo {
+    fn eq<V: ToSql + 'static>(&self, value: V) -> Expr {
+        Expr::compare(self, "=", Box::new(value))
+    }
+
+    fn ne<V: ToSql + 'static>(&self, value: V) -> Expr {
+        Expr::compare(self, "<>", Box::new(value))
+    }
+
+    fn gt<V: ToSql + 'static>(&self, value: V) -> Expr {
+        Expr::compare(self, ">", Box::new(value))
+    }
+
+    fn ge<V: ToSql + 'static>(&self, value: V) -> Expr {
+        Expr::compare(self, ">=", Box::new(value))
+    }
+
+    fn lt<V: ToSql + 'static>(&self, value: V) -> Expr {
+        Expr::compare(self, "<", Box::new(value))
+    }
+
+    fn le<V: ToSql + 'static>(&self, value: V) -> Expr {
+        Expr::compare(self, "<=", Box::new(value))
+    }
+
+    fn like<V: ToSql + 'static>(&self, pattern: V) -> Expr {
+        Expr::compare(self, "LIKE", Box::new(pattern))
+    }
+
+    fn is_in<V, I>(&self, values: I) -> Expr
+    where
+        V: ToSql + 'static,
+        I: IntoIterator<Item = V>,
+    {
+        let values = values
+            .into_iter()
+            .map(|value| Box::new(value) as Box<dyn ToSql>)
+            .collect();
+        Expr::in_list(self, values, false)
+    }
+
+    fn not_in<V, I>(&self, values: I) -> Expr
+    where
+        V: ToSql + 'static,
+        I: IntoIterator<Item = V>,
+    {
+        let values = values
+            .into_iter()
+            .map(|value| Box::new(value) as Box<dyn ToSql>)
+            .collect();
+        Expr::in_list(self, values, true)
+    }
+
+    fn is_null(&self) -> Expr {
+        Expr::null(self, false)
+    }
+
+    fn is_not_null(&self) -> Expr {
+        Expr::null(self, true)
+    }
 }
@@ -1,9 +1,42 @@
 use rusqlite::types::FromSql;
 
+/// Read a value from a row of a query
+/// # details
+/// - once a struct has `#[table]` or any `#[column]`, every field must be marked with `#[column]`
+///   or `#[column(skip)]` unless it has `#[table(all_columns)]`, a field is never dropped silently
+///
+/// ```compile_fail
+/// use tablex_rusqlite::FromRow;
+///
+/// #[derive(FromRow)]
+/// struct UserInfo {
+///     #[column]
+///     id: u32,
+///     name: String,
+/// }
+/// ```
 pub trait FromRow {
     fn from_row(row: &rusqlite::Row) -> rusqlite::Result<Self>
     where
         Self: Sized;
+
+    /// The indices of the columns the fields are read from, looked up once per statement
+    /// # details
+    /// - empty for the types which read the columns by position
+    fn column_indices(_stmt: &rusqlite::Statement) -> rusqlite::Result<Vec<usize>>
+    where
+        Self: Sized,
+    {
+        Ok(Vec::new())
+    }
+
+    /// Like `from_row`, with the `indices` returned by `column_indices`
+    fn from_row_with(row: &rusqlite::Row, _indices: &[usize]) -> rusqlite::Result<Self>
+    where
+        Self: Sized,
+    {
+        Self::from_row(row)
+    }
 }
 
 macro_rules! impl_from_row {
@@ -31,6 +31,20 @@ pub use executor::*;
 mod exts;
 pub use exts::*;
 
+mod columns;
+pub use columns::*;
+
+mod expr;
+pub use expr::*;
+
+mod transaction;
+pub use transaction::TransactionOptions;
+
+mod value;
+
+mod error;
+pub use error::{Error, Result};
+
 pub enum Conflict {
     Rollback,
     Abort,
@@ -49,4 +63,18 @@ impl Display for Conflict {
             Conflict::Replace => write!(f, "REPLACE"),
         }
     }
-}
\ No newline at end of file
+}
+
+pub enum Order {
+    Asc,
+    Desc,
+}
+
+impl Display for Order {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Order::Asc => write!(f, "ASC"),
+            Order::Desc => write!(f, "DESC"),
+        }
+    }
+}
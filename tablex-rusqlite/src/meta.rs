@@ -1,5 +1,8 @@
+use std::fmt::Display;
+
 use tablex::{Column, TableInfo};
 
+#[derive(Debug)]
 pub struct SqlExtraColumnInfo {
     /// The data type of the column, e.g., "TEXT", "INTEGER"
     pub data_type: &'static str,
@@ -13,16 +16,77 @@ pub struct SqlExtraColumnInfo {
     pub is_not_null: bool,
     /// Optional reference to another table/column
     pub reference: Option<Reference>,
+    /// The SQL expression of `DEFAULT`, e.g. "CURRENT_TIMESTAMP"
+    pub default: Option<&'static str>,
+    /// The SQL expression of `CHECK`, e.g. "age >= 0"
+    pub check: Option<&'static str>,
+    /// The collation of `COLLATE`, e.g. "NOCASE"
+    pub collate: Option<&'static str>,
+    /// The SQL expression of a generated column, e.g. "price * qty"
+    /// # details
+    /// - the column is never inserted or updated, but it is selected
+    pub generated: Option<&'static str>,
+    /// Whether the generated column is `STORED` instead of `VIRTUAL`
+    pub is_stored: bool,
 }
 
-
+#[derive(Debug)]
 pub struct Reference {
     pub table: &'static SqlTableInfo,
     pub column: &'static SqlColumnInfo,
+    /// `ON DELETE` action, SQLite uses `NO ACTION` when not set
+    pub on_delete: Option<ForeignKeyAction>,
+    /// `ON UPDATE` action, SQLite uses `NO ACTION` when not set
+    pub on_update: Option<ForeignKeyAction>,
+    /// Whether the constraint is `DEFERRABLE INITIALLY DEFERRED`, i.e. checked on commit
+    pub is_deferrable: bool,
+}
+
+/// The action of a foreign key when the referenced row is deleted or updated
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ForeignKeyAction {
+    NoAction,
+    Restrict,
+    SetNull,
+    SetDefault,
+    Cascade,
 }
 
+impl Display for ForeignKeyAction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ForeignKeyAction::NoAction => write!(f, "NO ACTION"),
+            ForeignKeyAction::Restrict => write!(f, "RESTRICT"),
+            ForeignKeyAction::SetNull => write!(f, "SET NULL"),
+            ForeignKeyAction::SetDefault => write!(f, "SET DEFAULT"),
+            ForeignKeyAction::Cascade => write!(f, "CASCADE"),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct SqlExtraTableInfo {
+    /// The columns of the primary key, in order, more than one for a composite key
+    pub primary_key: Vec<&'static SqlColumnInfo>,
+    /// The table-level `UNIQUE (a, b)` constraints
+    pub unique_constraints: Vec<Vec<&'static SqlColumnInfo>>,
+    /// The indexes created with the table
+    pub indexes: Vec<Index>,
+    /// Whether the table is `STRICT`, i.e. the values must match the column types
+    pub is_strict: bool,
+    /// Whether the table is `WITHOUT ROWID`, it must have a primary key
+    pub is_without_rowid: bool,
+}
+
+#[derive(Debug)]
+pub struct Index {
+    pub name: &'static str,
+    pub columns: Vec<&'static SqlColumnInfo>,
+    /// Whether this is a `UNIQUE` index
+    pub is_unique: bool,
+}
 
-pub type SqlTableInfo = TableInfo<(), SqlExtraColumnInfo>;
+pub type SqlTableInfo = TableInfo<SqlExtraTableInfo, SqlExtraColumnInfo>;
 pub type SqlColumnInfo = Column<SqlExtraColumnInfo>;
 
 #[cfg(test)]
@@ -31,7 +95,7 @@ mod test {
 
     use tablex::{Column, Table, TableInfo};
 
-    use crate::{meta::{Reference, SqlColumnInfo, SqlExtraColumnInfo, SqlTableInfo},Params};
+    use crate::{meta::{Reference, SqlColumnInfo, SqlExtraColumnInfo, SqlExtraTableInfo, SqlTableInfo},Params};
 
     #[derive(Debug)]
     struct UserInfo {
@@ -47,18 +111,25 @@ mod test {
     }
 
     impl UserInfo {
-        pub const fn const_table_info() -> &'static SqlTableInfo {
+        pub fn const_table_info() -> &'static SqlTableInfo {
             static COLUMNS: [&SqlColumnInfo; 3] = [
                 UserInfo::column_id(),
                 UserInfo::column_user_name(),
                 UserInfo::column_age(),
             ];
 
-            static TABLE_INFO: SqlTableInfo = SqlTableInfo {
+            // 主键列表需要分配, 不能在常量中构造
+            static TABLE_INFO: LazyLock<SqlTableInfo> = LazyLock::new(|| SqlTableInfo {
                 table_name: "UserInfo",
                 columns: &COLUMNS,
-                extra: (),
-            };
+                extra: SqlExtraTableInfo {
+                    primary_key: vec![UserInfo::column_id()],
+                    unique_constraints: Vec::new(),
+                    indexes: Vec::new(),
+                    is_strict: false,
+                    is_without_rowid: false,
+                },
+            });
 
             &TABLE_INFO
         }
@@ -76,6 +147,11 @@ mod test {
                     is_not_null: true,
                     is_auto_increment: false,
                     reference: None,
+                    default: None,
+                    check: None,
+                    collate: None,
+                    generated: None,
+                    is_stored: false,
                 },
             };
             &COLUMN
@@ -94,6 +170,11 @@ mod test {
                     is_auto_increment: false,
                     is_not_null: true,
                     reference: None,
+                    default: None,
+                    check: None,
+                    collate: None,
+                    generated: None,
+                    is_stored: false,
                 },
             };
             &COLUMN
@@ -112,6 +193,11 @@ mod test {
                     is_auto_increment: false,
                     is_not_null: true,
                     reference: None,
+                    default: None,
+                    check: None,
+                    collate: None,
+                    generated: None,
+                    is_stored: false,
                 },
             };
             &COLUMN
@@ -119,7 +205,7 @@ mod test {
     }
 
     impl Table for UserInfo {
-        type ExtraTableInfo = ();
+        type ExtraTableInfo = SqlExtraTableInfo;
 
         type ExtraColumnInfo = SqlExtraColumnInfo;
 
@@ -172,10 +258,16 @@ mod test {
             static TABLE_INFO: LazyLock<SqlTableInfo> = LazyLock::new(|| SqlTableInfo {
                 table_name: "Transaction",
                 columns: &*COLUMNS,
-                extra: (),
+                extra: SqlExtraTableInfo {
+                    primary_key: Vec::new(),
+                    unique_constraints: Vec::new(),
+                    indexes: Vec::new(),
+                    is_strict: false,
+                    is_without_rowid: false,
+                },
             });
 
-            &*TABLE_INFO
+            &TABLE_INFO
         }
 
         pub fn column_from_id() -> &'static SqlColumnInfo {
@@ -193,7 +285,15 @@ mod test {
                     reference: Some(Reference {
                         table: UserInfo::table_info(),
                         column: UserInfo::column_id(),
+                        on_delete: None,
+                        on_update: None,
+                        is_deferrable: false,
                     }),
+                    default: None,
+                    check: None,
+                    collate: None,
+                    generated: None,
+                    is_stored: false,
                 },
             });
 
@@ -215,7 +315,15 @@ mod test {
                     reference: Some(Reference {
                         table: UserInfo::table_info(),
                         column: UserInfo::column_id(),
+                        on_delete: None,
+                        on_update: None,
+                        is_deferrable: false,
                     }),
+                    default: None,
+                    check: None,
+                    collate: None,
+                    generated: None,
+                    is_stored: false,
                 },
             });
             &COLUMN
@@ -223,7 +331,7 @@ mod test {
     }
 
     impl Table for Transaction {
-        type ExtraTableInfo = ();
+        type ExtraTableInfo = SqlExtraTableInfo;
         type ExtraColumnInfo = SqlExtraColumnInfo;
 
         fn table_info() -> &'static TableInfo<Self::ExtraTableInfo, Self::ExtraColumnInfo> {
@@ -256,6 +364,20 @@ mod test {
         assert_eq!(table_info.table_name, "UserInfo");
         assert_eq!(table_info.columns.len(), 3);
 
+        // 主键列表与列的 is_primary 一致
+        for table_info in [UserInfo::table_info(), Transaction::table_info()] {
+            for column in table_info.columns {
+                assert_eq!(
+                    column.extra.is_primary,
+                    table_info
+                        .extra
+                        .primary_key
+                        .iter()
+                        .any(|key| std::ptr::eq(*key, *column))
+                );
+            }
+        }
+
         let user_name_column = UserInfo::column_user_name();
         let age_column = UserInfo::column_age();
 
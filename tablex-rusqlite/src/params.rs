@@ -1,5 +1,36 @@
 use rusqlite::{BindIndex, Statement, ToSql, types::Null};
 
+use crate::{Error, meta::SqlColumnInfo};
+
+/// The params bound to a statement, by name or by position
+/// # details
+/// - the insert and update statements of a `Table` bind `:field_name` of every column,
+///   so a column can not be renamed with `#[param(rename)]`
+///
+/// ```compile_fail
+/// use tablex_rusqlite::{Params, Table};
+///
+/// #[derive(Table, Params)]
+/// #[table(name = "user_info", all_columns)]
+/// struct UserInfo {
+///     #[param(rename = "user_name")]
+///     name: String,
+/// }
+/// ```
+///
+/// - nor skipped with `#[param(skip)]`, use `#[column(skip)]` for a field which is not a column
+///
+/// ```compile_fail
+/// use tablex_rusqlite::{Params, Table};
+///
+/// #[derive(Table, Params)]
+/// #[table(name = "user_info", all_columns)]
+/// struct UserInfo {
+///     id: u32,
+///     #[param(skip)]
+///     name: String,
+/// }
+/// ```
 pub trait Params {
     type BindIndex: BindIndex;
 
@@ -17,6 +48,70 @@ where
     Ok(())
 }
 
+/// Bind `params` to the placeholders of the user's SQL in a statement built by a builder,
+/// e.g. `filter_raw("age > ?1")` next to a filter expression
+/// # details
+/// - the placeholders of the builder (`:__tablex_...`) are never bound, so their values are kept
+/// - a param without a placeholder of its own is a `Misuse`
+pub(crate) fn bind_user_params<'a, I, BI>(params: I, stmt: &mut Statement) -> crate::Result<()>
+where
+    BI: BindIndex,
+    I: IntoIterator<Item = (BI, &'a dyn ToSql)>,
+{
+    for (index, value) in params {
+        let index = index.idx(stmt)?;
+
+        let is_own = (1..=stmt.parameter_count()).contains(&index)
+            && !stmt
+                .parameter_name(index)
+                .is_some_and(|name| name.starts_with(":__tablex_"));
+        if !is_own {
+            return Err(Error::Misuse(format!(
+                "param {} has no placeholder of its own in the statement",
+                index
+            )));
+        }
+
+        stmt.raw_bind_parameter(index, value)?;
+    }
+    Ok(())
+}
+
+/// Bind the named params of a table row to a statement built from the table's `columns`
+/// # details
+/// - params without a placeholder in the statement are not bound, e.g. a field which is not a column
+/// - every column must have a param named `:field_name`
+pub(crate) fn bind_column_params<'a, P, I>(
+    table_name: &'static str,
+    columns: I,
+    stmt: &mut Statement,
+    params: &P,
+) -> crate::Result<()>
+where
+    P: Params<BindIndex = &'static str>,
+    I: IntoIterator<Item = &'a SqlColumnInfo>,
+{
+    let mut bound = Vec::new();
+    for (name, value) in params.params() {
+        if stmt.parameter_index(name)?.is_some() {
+            stmt.raw_bind_parameter(name, value)?;
+            bound.push(name);
+        }
+    }
+
+    // 参数名带有`:`前缀
+    for column in columns {
+        if !bound.iter().any(|name| name[1..] == *column.field_name) {
+            return Err(Error::Misuse(format!(
+                "no param `:{}` for column `{}` of table `{}`",
+                column.field_name, column.column_name, table_name
+            )));
+        }
+    }
+
+    Ok(())
+}
+
 impl Params for () {
     type BindIndex = usize;
 
@@ -2,18 +2,39 @@ mod create_table;
 
 pub use create_table::*;
 
+mod filter;
+pub(crate) use filter::*;
+
 mod drop_table;
 pub use drop_table::*;
 
 mod insert_row;
 pub use insert_row::*;
 
+mod on_conflict;
+pub use on_conflict::*;
+
 mod select_rows;
 pub use select_rows::*;
 
+mod select_page;
+pub use select_page::*;
+
+mod find_row;
+pub use find_row::*;
+
 mod returning_row;
 pub use returning_row::*;
 
+mod update_row;
+pub use update_row::*;
+
+mod delete_rows;
+pub use delete_rows::*;
+
+mod returning_rows;
+pub use returning_rows::*;
+
 use crate::SqlTable;
 
 pub fn create_table<T>() -> CreateTableBuilder<T>
@@ -43,4 +64,25 @@ where
     T: SqlTable,
 {
     SelectRowsBuilder::new()
-}
\ No newline at end of file
+}
+
+pub fn find<T>() -> FindRowBuilder<T>
+where
+    T: SqlTable,
+{
+    FindRowBuilder::new()
+}
+
+pub fn update_row<T>() -> UpdateRowBuilder<T>
+where
+    T: SqlTable,
+{
+    UpdateRowBuilder::new()
+}
+
+pub fn delete_rows<T>() -> DeleteRowsBuilder<T>
+where
+    T: SqlTable,
+{
+    DeleteRowsBuilder::new()
+}
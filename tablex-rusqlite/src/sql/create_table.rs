@@ -1,6 +1,8 @@
 use std::marker::PhantomData;
 
-use crate::{Builder, Executor, SqlTable};
+use crate::{
+    Builder, Executor, SqlTable, error::SqlContext, meta::SqlColumnInfo, transaction::savepoint,
+};
 
 #[derive(Debug)]
 pub struct CreateTableBuilder<T> {
@@ -15,7 +17,7 @@ where
     pub(crate) fn new() -> Self {
         CreateTableBuilder {
             create_if_not_exists: false,
-            _marker: PhantomData::default(),
+            _marker: PhantomData,
         }
     }
 
@@ -27,39 +29,56 @@ where
     fn build_sql(&self) -> String {
         let table_info = T::table_info();
 
-        let if_not_exists = self
-            .create_if_not_exists
-            .then_some("IF NOT EXISTS")
-            .unwrap_or_default();
+        let if_not_exists = if self.create_if_not_exists { "IF NOT EXISTS" } else { "" };
 
-        let columns = table_info
+        // 只有一个主键列时写在列上, 否则作为表约束
+        let primary_key_columns = T::primary_key_columns();
+        let is_inline_primary_key = primary_key_columns.len() == 1;
+
+        let mut columns = table_info
             .columns
             .iter()
             .map(|column| {
                 let data_type = column.extra.data_type;
 
-                let primary_key = column
+                let primary_key = if is_inline_primary_key && column.extra.is_primary {
+                    "PRIMARY KEY"
+                } else {
+                    ""
+                };
+
+                let auto_increment = if column.extra.is_auto_increment { "AUTOINCREMENT" } else { "" };
+
+                let is_unique = if column.extra.is_unique { "UNIQUE" } else { "" };
+
+                let not_null = if column.extra.is_not_null { "NOT NULL" } else { "" };
+
+                // 表达式加上括号, 使任意表达式都可以作为默认值
+                let default = column
                     .extra
-                    .is_primary
-                    .then_some("PRIMARY KEY")
+                    .default
+                    .map(|default| format!("DEFAULT ({})", default))
                     .unwrap_or_default();
 
-                let auto_increment = column
+                let check = column
                     .extra
-                    .is_auto_increment
-                    .then_some("AUTOINCREMENT")
+                    .check
+                    .map(|check| format!("CHECK ({})", check))
                     .unwrap_or_default();
 
-                let is_unique = column
+                let collate = column
                     .extra
-                    .is_unique
-                    .then_some("UNIQUE")
+                    .collate
+                    .map(|collate| format!("COLLATE {}", collate))
                     .unwrap_or_default();
 
-                let not_null = column
+                let generated = column
                     .extra
-                    .is_not_null
-                    .then_some("NOT NULL")
+                    .generated
+                    .map(|generated| {
+                        let storage = if column.extra.is_stored { "STORED" } else { "VIRTUAL" };
+                        format!("GENERATED ALWAYS AS ({}) {}", generated, storage)
+                    })
                     .unwrap_or_default();
 
                 let reference = column
@@ -67,32 +86,97 @@ where
                     .reference
                     .as_ref()
                     .map(|reference| {
-                        format!(
+                        let mut reference_sql = format!(
                             "REFERENCES {}({})",
                             reference.table.table_name, reference.column.column_name
-                        )
+                        );
+                        if let Some(action) = reference.on_delete {
+                            reference_sql.push_str(&format!(" ON DELETE {}", action));
+                        }
+                        if let Some(action) = reference.on_update {
+                            reference_sql.push_str(&format!(" ON UPDATE {}", action));
+                        }
+                        if reference.is_deferrable {
+                            reference_sql.push_str(" DEFERRABLE INITIALLY DEFERRED");
+                        }
+                        reference_sql
                     })
                     .unwrap_or_default();
 
                 format!(
-                    "{} {} {} {} {} {} {}",
+                    "{} {} {} {} {} {} {} {} {} {} {}",
                     column.column_name,
                     data_type,
                     primary_key,
                     auto_increment,
                     is_unique,
                     not_null,
+                    default,
+                    check,
+                    collate,
+                    generated,
                     reference
                 )
             })
-            .collect::<Vec<_>>()
-            .join(", ");
+            .collect::<Vec<_>>();
+
+        if !is_inline_primary_key && !primary_key_columns.is_empty() {
+            columns.push(format!("PRIMARY KEY ({})", column_names(primary_key_columns)));
+        }
+
+        for unique_columns in &table_info.extra.unique_constraints {
+            columns.push(format!("UNIQUE ({})", column_names(unique_columns)));
+        }
+
+        let mut options = Vec::new();
+        if table_info.extra.is_strict {
+            options.push("STRICT");
+        }
+        if table_info.extra.is_without_rowid {
+            options.push("WITHOUT ROWID");
+        }
 
         format!(
-            "CREATE TABLE {} {} ({})",
-            if_not_exists, table_info.table_name, columns
+            "CREATE TABLE {} {} ({}) {}",
+            if_not_exists,
+            table_info.table_name,
+            columns.join(", "),
+            options.join(", ")
         )
     }
+
+    /// `CREATE INDEX` of every index of the table
+    fn build_index_sqls(&self) -> Vec<String> {
+        let table_info = T::table_info();
+
+        let if_not_exists = if self.create_if_not_exists { "IF NOT EXISTS" } else { "" };
+
+        table_info
+            .extra
+            .indexes
+            .iter()
+            .map(|index| {
+                let unique = if index.is_unique { "UNIQUE" } else { "" };
+
+                format!(
+                    "CREATE {} INDEX {} {} ON {} ({})",
+                    unique,
+                    if_not_exists,
+                    index.name,
+                    table_info.table_name,
+                    column_names(&index.columns)
+                )
+            })
+            .collect()
+    }
+}
+
+fn column_names(columns: &[&'static SqlColumnInfo]) -> String {
+    columns
+        .iter()
+        .map(|column| column.column_name)
+        .collect::<Vec<_>>()
+        .join(", ")
 }
 
 impl<T> Builder<()> for CreateTableBuilder<T>
@@ -103,21 +187,35 @@ where
 
     fn build<'connection>(
         &self,
-        connection: &'connection  rusqlite::Connection,
+        connection: &'connection rusqlite::Connection,
         _: &(),
-    ) -> rusqlite::Result<Self::Executor<'connection>> {
-        let sql = self.build_sql();
-        let stmt = connection.prepare(&sql)?;
+    ) -> crate::Result<Self::Executor<'connection>> {
+        let context = SqlContext::new(self.build_sql(), Some(T::table_info()));
+        let stmt = context.prepare(connection)?;
+
+        // 索引要在表创建后才能准备语句
+        let index_contexts = self
+            .build_index_sqls()
+            .into_iter()
+            .map(|sql| SqlContext::new(sql, Some(T::table_info())))
+            .collect();
 
         Ok(CreateTableExecutor{
+            connection,
             stmt,
-            _marker: PhantomData::default(),
+            context,
+            index_contexts,
+            _marker: PhantomData,
         })
     }
 }
 
+/// Create the table and its indexes in one savepoint
 pub struct CreateTableExecutor<'conn, T> {
+    connection: &'conn rusqlite::Connection,
     stmt: rusqlite::Statement<'conn>,
+    context: SqlContext,
+    index_contexts: Vec<SqlContext>,
     _marker: PhantomData<T>,
 }
 
@@ -127,12 +225,36 @@ where
 {
     type Output = ();
 
-    fn execute(&mut self) -> rusqlite::Result<Self::Output> {
-        self.stmt.raw_execute()?;
-        Ok(())
+    fn execute(&mut self) -> crate::Result<Self::Output> {
+        savepoint(self.connection, |connection| {
+            self.stmt
+                .raw_execute()
+                .map_err(|err| self.context.error(err))?;
+
+            for context in &self.index_contexts {
+                context
+                    .prepare(connection)?
+                    .raw_execute()
+                    .map_err(|err| context.error(err))?;
+            }
+            Ok(())
+        })
     }
 
+    /// The `CREATE TABLE` followed by the `CREATE INDEX` statements, separated by `;`
     fn sql(&mut self) -> String {
-        self.stmt.expanded_sql().unwrap()
+        let table_sql = self
+            .stmt
+            .expanded_sql()
+            .unwrap_or_else(|| self.context.sql().to_string());
+
+        std::iter::once(table_sql)
+            .chain(
+                self.index_contexts
+                    .iter()
+                    .map(|context| context.sql().to_string()),
+            )
+            .collect::<Vec<_>>()
+            .join("; ")
     }
 }
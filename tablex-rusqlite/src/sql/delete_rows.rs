@@ -0,0 +1,174 @@
+use std::marker::PhantomData;
+
+use rusqlite::types::Value;
+
+use crate::{
+    Builder, Error, ExecuteResult, Executor, Expr, SqlTable, bind_params,
+    error::SqlContext,
+    meta::SqlColumnInfo,
+    sql::{Filter, ReturningRowsBuilder},
+    value::owned_column_value,
+};
+
+enum DeleteFilter {
+    All,
+    Filter(Filter),
+    PrimaryKey(Vec<(&'static SqlColumnInfo, Option<Value>)>),
+}
+
+pub struct DeleteRowsBuilder<Table> {
+    filter: DeleteFilter,
+    _marker: PhantomData<Table>,
+}
+
+impl<Table> DeleteRowsBuilder<Table>
+where
+    Table: SqlTable,
+{
+    pub(crate) fn new() -> Self {
+        DeleteRowsBuilder {
+            filter: DeleteFilter::All,
+            _marker: PhantomData,
+        }
+    }
+
+    pub fn filter_raw(mut self, raw_where_clause: impl Into<String>) -> Self {
+        self.filter = DeleteFilter::Filter(Filter::Raw(raw_where_clause.into()));
+        self
+    }
+
+    pub fn filter(mut self, expr: Expr) -> Self {
+        self.filter = DeleteFilter::Filter(Filter::Expr(expr));
+        self
+    }
+
+    /// Delete the row which has the same primary key as `table`
+    pub fn by_primary_key(mut self, table: &Table) -> Self {
+        let key_values = Table::primary_key_columns()
+            .iter()
+            .map(|column| (*column, owned_column_value(table, column)))
+            .collect();
+
+        self.filter = DeleteFilter::PrimaryKey(key_values);
+        self
+    }
+
+    pub fn returning_rows(self) -> ReturningRowsBuilder<Table> {
+        ReturningRowsBuilder::new(self)
+    }
+}
+
+impl<T> DeleteRowsBuilder<T>
+where
+    T: SqlTable,
+{
+    pub(crate) fn build_sql(&self) -> crate::Result<String> {
+        let table_info = T::table_info();
+        let table_name = table_info.table_name;
+
+        match &self.filter {
+            DeleteFilter::All => Ok(format!("DELETE FROM {}", table_name)),
+            DeleteFilter::Filter(filter) => Ok(format!(
+                "DELETE FROM {} WHERE {}",
+                table_name,
+                filter.build_sql::<T>()?
+            )),
+            DeleteFilter::PrimaryKey(key_values) => {
+                if key_values.is_empty() {
+                    return Err(Error::MissingPrimaryKey { table: table_name });
+                }
+
+                let conditions = key_values
+                    .iter()
+                    .enumerate()
+                    .map(|(index, (column, _))| format!("{} = ?{}", column.column_name, index + 1))
+                    .collect::<Vec<_>>();
+
+                Ok(format!(
+                    "DELETE FROM {} WHERE {}",
+                    table_name,
+                    conditions.join(" AND ")
+                ))
+            }
+        }
+    }
+
+    pub(crate) fn bind_delete_params(
+        &self,
+        stmt: &mut rusqlite::Statement<'_>,
+    ) -> crate::Result<()> {
+        if let DeleteFilter::Filter(filter) = &self.filter {
+            filter.bind_values(stmt)?;
+        }
+
+        if let DeleteFilter::PrimaryKey(key_values) = &self.filter {
+            let mut params = Vec::with_capacity(key_values.len());
+            for (index, (column, value)) in key_values.iter().enumerate() {
+                // 主键字段的类型不是内置的 SqlType 时无法读取
+                let value = value.as_ref().ok_or(Error::UnreadableValue {
+                    table: T::table_info().table_name,
+                    column,
+                })?;
+                params.push((index + 1, value as &dyn rusqlite::ToSql));
+            }
+            bind_params(params, stmt)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl<T> Builder<()> for DeleteRowsBuilder<T>
+where
+    T: SqlTable,
+{
+    type Executor<'connection> = DeleteRowsExecutor<'connection, T>;
+
+    fn build<'connection>(
+        &self,
+        connection: &'connection rusqlite::Connection,
+        _params: &(),
+    ) -> crate::Result<Self::Executor<'connection>> {
+        let context = SqlContext::new(self.build_sql()?, Some(T::table_info()));
+
+        let mut stmt = context.prepare(connection)?;
+
+        self.bind_delete_params(&mut stmt)
+            .map_err(|err| context.error(err))?;
+
+        Ok(DeleteRowsExecutor {
+            connection,
+            stmt,
+            context,
+            _marker: PhantomData,
+        })
+    }
+}
+
+pub struct DeleteRowsExecutor<'conn, T> {
+    connection: &'conn rusqlite::Connection,
+    stmt: rusqlite::Statement<'conn>,
+    context: SqlContext,
+    _marker: PhantomData<T>,
+}
+
+impl<'conn, T> Executor for DeleteRowsExecutor<'conn, T>
+where
+    T: SqlTable,
+{
+    type Output = ExecuteResult;
+
+    fn execute(&mut self) -> crate::Result<Self::Output> {
+        let rows_affected = self
+            .stmt
+            .raw_execute()
+            .map_err(|err| self.context.error(err))?;
+        Ok(ExecuteResult::new(self.connection, rows_affected))
+    }
+
+    fn sql(&mut self) -> String {
+        self.stmt
+            .expanded_sql()
+            .unwrap_or_else(|| self.context.sql().to_string())
+    }
+}
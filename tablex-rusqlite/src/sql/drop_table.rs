@@ -1,7 +1,8 @@
 use std::marker::PhantomData;
 
-use crate::{Builder, Executor, SqlTable};
+use crate::{Builder, Executor, SqlTable, error::SqlContext};
 
+/// Drop the table, SQLite drops the indexes of the table with it
 pub struct DropTableBuilder<T> {
     drop_if_exists: bool,
     _marker: PhantomData<T>,
@@ -14,7 +15,7 @@ where
     pub(crate) fn new() -> Self {
         DropTableBuilder {
             drop_if_exists: false,
-            _marker: PhantomData::default(),
+            _marker: PhantomData,
         }
     }
 
@@ -30,10 +31,7 @@ where
 {
     fn build_sql(&self) -> String {
         let table_name = T::table_info().table_name;
-        let if_exists = self
-            .drop_if_exists
-            .then_some("IF EXISTS")
-            .unwrap_or_default();
+        let if_exists = if self.drop_if_exists { "IF EXISTS" } else { "" };
 
         format!("DROP TABLE {} {}", if_exists, table_name)
     }
@@ -50,11 +48,12 @@ where
         &self,
         connection: &'connection rusqlite::Connection,
         _params: &(),
-    ) -> rusqlite::Result<Self::Executor<'connection>> {
-        let sql = self.build_sql();
-        let stmt = connection.prepare(&sql)?;
+    ) -> crate::Result<Self::Executor<'connection>> {
+        let context = SqlContext::new(self.build_sql(), Some(T::table_info()));
+        let stmt = context.prepare(connection)?;
         Ok(DropTableExecutor {
             stmt,
+            context,
             _marker: PhantomData,
         })
     }
@@ -63,6 +62,7 @@ where
 
 pub struct DropTableExecutor<'conn, T> {
     stmt: rusqlite::Statement<'conn>,
+    context: SqlContext,
     _marker: PhantomData<T>,
 }
 
@@ -72,12 +72,16 @@ where
 {
     type Output = ();
 
-    fn execute(&mut self) -> rusqlite::Result<Self::Output> {
-        self.stmt.raw_execute()?;
+    fn execute(&mut self) -> crate::Result<Self::Output> {
+        self.stmt
+            .raw_execute()
+            .map_err(|err| self.context.error(err))?;
         Ok(())
     }
 
     fn sql(&mut self) -> String {
-        self.stmt.expanded_sql().unwrap()
+        self.stmt
+            .expanded_sql()
+            .unwrap_or_else(|| self.context.sql().to_string())
     }
 }
@@ -0,0 +1,29 @@
+use crate::{Expr, SqlTable};
+
+/// The `WHERE` clause shared by the builders
+pub(crate) enum Filter {
+    Raw(String),
+    Expr(Expr),
+}
+
+impl Filter {
+    pub(crate) fn build_sql<T>(&self) -> crate::Result<String>
+    where
+        T: SqlTable,
+    {
+        match self {
+            Filter::Raw(raw_where_clause) => Ok(raw_where_clause.clone()),
+            Filter::Expr(expr) => {
+                expr.check_table::<T>()?;
+                Ok(expr.build_sql())
+            }
+        }
+    }
+
+    pub(crate) fn bind_values(&self, stmt: &mut rusqlite::Statement<'_>) -> rusqlite::Result<()> {
+        match self {
+            Filter::Raw(_) => Ok(()),
+            Filter::Expr(expr) => expr.bind_values(stmt),
+        }
+    }
+}
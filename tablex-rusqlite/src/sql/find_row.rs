@@ -0,0 +1,110 @@
+use std::marker::PhantomData;
+
+use crate::{
+    Builder, Error, ExecuteWith, Executor, FromRow, Params, SqlTable, bind_user_params,
+    error::SqlContext,
+};
+
+/// Find the row by primary key, the key is passed as params in the order of the primary key
+/// columns, e.g. `&(1,)`
+pub struct FindRowBuilder<Table> {
+    _marker: PhantomData<Table>,
+}
+
+impl<Table> FindRowBuilder<Table>
+where
+    Table: SqlTable,
+{
+    pub(crate) fn new() -> Self {
+        FindRowBuilder {
+            _marker: PhantomData,
+        }
+    }
+
+    fn build_sql(&self) -> crate::Result<String> {
+        let table_info = Table::table_info();
+        let table_name = table_info.table_name;
+
+        let conditions = Table::primary_key_columns()
+            .iter()
+            .enumerate()
+            .map(|(index, column)| format!("{} = ?{}", column.column_name, index + 1))
+            .collect::<Vec<_>>();
+
+        if conditions.is_empty() {
+            return Err(Error::MissingPrimaryKey { table: table_name });
+        }
+
+        Ok(format!(
+            "SELECT * FROM {} WHERE {}",
+            table_name,
+            conditions.join(" AND ")
+        ))
+    }
+}
+
+impl<Table, P> Builder<P> for FindRowBuilder<Table>
+where
+    Table: SqlTable + FromRow,
+    P: Params,
+{
+    type Executor<'connection> = FindRowExecutor<'connection, Table>;
+
+    fn build<'connection>(
+        &self,
+        connection: &'connection rusqlite::Connection,
+        params: &P,
+    ) -> crate::Result<Self::Executor<'connection>> {
+        let context = SqlContext::new(self.build_sql()?, Some(Table::table_info()));
+
+        let mut stmt = context.prepare(connection)?;
+
+        bind_user_params(params.params(), &mut stmt).map_err(|err| context.error(err))?;
+
+        Ok(FindRowExecutor {
+            stmt,
+            context,
+            _marker: PhantomData,
+        })
+    }
+}
+
+pub struct FindRowExecutor<'connection, Table> {
+    stmt: rusqlite::Statement<'connection>,
+    context: SqlContext,
+    _marker: PhantomData<Table>,
+}
+
+impl<Table> Executor for FindRowExecutor<'_, Table>
+where
+    Table: SqlTable + FromRow,
+{
+    /// `None` when no row has the key
+    type Output = Option<Table>;
+
+    fn execute(&mut self) -> crate::Result<Self::Output> {
+        let mut rows = self.stmt.raw_query();
+
+        match rows.next().map_err(|err| self.context.error(err))? {
+            Some(row) => self.context.decode(row).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    fn sql(&mut self) -> String {
+        self.stmt
+            .expanded_sql()
+            .unwrap_or_else(|| self.context.sql().to_string())
+    }
+}
+
+impl<Table, P> ExecuteWith<P> for FindRowExecutor<'_, Table>
+where
+    Table: SqlTable + FromRow,
+    P: Params,
+{
+    fn execute_with(&mut self, params: &P) -> crate::Result<Self::Output> {
+        bind_user_params(params.params(), &mut self.stmt).map_err(|err| self.context.error(err))?;
+        self.execute()
+    }
+}
@@ -1,13 +1,38 @@
 use std::marker::PhantomData;
 
-use crate::{Builder, Conflict, Executor, Params, SqlTable, bind_params, sql::ReturningRowBuilder};
+use crate::{
+    Builder, BuilderMut, Columns, Conflict, Error, ExecuteResult, ExecuteWith, Executor, Params,
+    SqlTable, bind_column_params,
+    error::SqlContext,
+    meta::SqlColumnInfo,
+    sql::{OnConflictBuilder, ReturningRowBuilder, Upsert, UpsertAction},
+    transaction::savepoint,
+    value::set_rowid_value,
+};
 
 pub struct InsertRowBuilder<Table> {
     conflict: Option<Conflict>,
-    with_auto_increment: bool,
+    upsert: Option<Upsert>,
+    pub(crate) omitted_columns: OmittedColumns,
     _marker1: PhantomData<Table>,
 }
 
+/// The columns an insert leaves to SQLite, generated columns are always left
+#[derive(Debug, Clone, Copy, Default)]
+pub(crate) struct OmittedColumns {
+    auto_increment: bool,
+    defaults: bool,
+}
+
+impl OmittedColumns {
+    fn omits(&self, column: &SqlColumnInfo) -> bool {
+        // 开启自增时，不需要发送自增的列
+        (self.auto_increment && column.extra.is_auto_increment)
+            || (self.defaults && column.extra.default.is_some())
+            || column.extra.generated.is_some()
+    }
+}
+
 impl<Table> InsertRowBuilder<Table>
 where
     Table: SqlTable,
@@ -15,8 +40,9 @@ where
     pub(crate) fn new() -> Self {
         InsertRowBuilder {
             conflict: None,
-            with_auto_increment: false,
-            _marker1: PhantomData::default(),
+            upsert: None,
+            omitted_columns: OmittedColumns::default(),
+            _marker1: PhantomData,
         }
     }
 }
@@ -30,21 +56,54 @@ where
         self
     }
 
+    /// Start an upsert clause `ON CONFLICT (columns) DO ...`
+    pub fn on_conflict(self, columns: impl Columns) -> OnConflictBuilder<Table> {
+        OnConflictBuilder::new(self, columns.into_columns())
+    }
+
+    pub(crate) fn upsert(mut self, upsert: Upsert) -> Self {
+        self.upsert = Some(upsert);
+        self
+    }
+
     pub fn with_auto_increment(mut self) -> Self {
-        self.with_auto_increment = true;
+        self.omitted_columns.auto_increment = true;
+        self
+    }
+
+    /// Leave the columns which have a `default` to SQLite, their fields are not sent
+    /// # details
+    /// - the fields are not written back by `build_mut`
+    pub fn with_defaults(mut self) -> Self {
+        self.omitted_columns.defaults = true;
         self
     }
 
+    /// Insert and return the inserted row
+    /// # details
+    /// - it can not be used with `or(Conflict::Ignore)` or `do_nothing`, a skipped insert returns no row
     pub fn returning_row(self) -> ReturningRowBuilder<Table> {
         ReturningRowBuilder::new(self)
     }
+
+    /// Whether a conflicting insert is skipped without an error
+    pub(crate) fn skips_conflicts(&self) -> bool {
+        matches!(self.conflict, Some(Conflict::Ignore))
+            || matches!(
+                self.upsert,
+                Some(Upsert {
+                    action: UpsertAction::Nothing,
+                    ..
+                })
+            )
+    }
 }
 
 impl<T> InsertRowBuilder<T>
 where
     T: SqlTable,
 {
-    pub(crate) fn build_sql(&self) -> String {
+    pub(crate) fn build_sql(&self) -> crate::Result<String> {
         let table_info = T::table_info();
         let table_name = table_info.table_name;
 
@@ -54,41 +113,95 @@ where
             .map(|conflict| format!(" OR {}", conflict))
             .unwrap_or_default();
 
-        let columns = table_info
-            .columns
-            .iter()
-            // 开启自增时，不需要发送自增的列
-            .filter(|column| {
-                if self.with_auto_increment {
-                    !column.extra.is_auto_increment
-                } else {
-                    true
-                }
-            })
+        let columns = insert_columns::<T>(self.omitted_columns)
             .map(|column| column.column_name)
             .collect::<Vec<_>>();
 
-        let placeholders = table_info
-            .columns
-            .iter()
-            // 开启自增时，不需要发送自增的列
-            .filter(|column| {
-                if self.with_auto_increment {
-                    !column.extra.is_auto_increment
-                } else {
-                    true
-                }
-            })
+        let placeholders = insert_columns::<T>(self.omitted_columns)
             .map(|column| format!(":{}", column.field_name))
             .collect::<Vec<_>>();
 
-        format!(
-            "INSERT {} INTO {} ({}) VALUES ({})",
+        let upsert = self
+            .upsert
+            .as_ref()
+            .map(|upsert| self.build_upsert_sql(upsert))
+            .transpose()?
+            .unwrap_or_default();
+
+        // 所有列都由 SQLite 填充
+        if columns.is_empty() {
+            return Ok(format!(
+                "INSERT {} INTO {} DEFAULT VALUES{}",
+                or, table_name, upsert
+            ));
+        }
+
+        Ok(format!(
+            "INSERT {} INTO {} ({}) VALUES ({}){}",
             or,
             table_name,
             columns.join(", "),
-            placeholders.join(", ")
-        )
+            placeholders.join(", "),
+            upsert
+        ))
+    }
+
+    fn build_upsert_sql(&self, upsert: &Upsert) -> crate::Result<String> {
+        let table_info = T::table_info();
+
+        // 冲突列和更新列都必须属于当前表
+        let columns = upsert.target.iter().chain(match &upsert.action {
+            UpsertAction::Nothing => [].iter(),
+            UpsertAction::Update(columns) => columns.iter(),
+        });
+        for column in columns {
+            if !table_info.has_column(column) {
+                return Err(Error::UnknownColumn {
+                    table: table_info.table_name,
+                    column,
+                });
+            }
+        }
+
+        // 更新列不能为空, 也不能是生成列
+        if let UpsertAction::Update(columns) = &upsert.action {
+            if columns.is_empty() {
+                return Err(Error::Misuse(format!(
+                    "the `DO UPDATE` of table `{}` has no columns to set",
+                    table_info.table_name
+                )));
+            }
+            if let Some(column) = columns.iter().find(|column| column.extra.generated.is_some()) {
+                return Err(Error::GeneratedColumn {
+                    table: table_info.table_name,
+                    column,
+                });
+            }
+        }
+
+        let target = if upsert.target.is_empty() {
+            String::new()
+        } else {
+            let target = upsert
+                .target
+                .iter()
+                .map(|column| column.column_name)
+                .collect::<Vec<_>>();
+            format!(" ({})", target.join(", "))
+        };
+
+        let action = match &upsert.action {
+            UpsertAction::Nothing => "NOTHING".to_string(),
+            UpsertAction::Update(columns) => {
+                let assignments = columns
+                    .iter()
+                    .map(|column| format!("{0} = excluded.{0}", column.column_name))
+                    .collect::<Vec<_>>();
+                format!("UPDATE SET {}", assignments.join(", "))
+            }
+        };
+
+        Ok(format!(" ON CONFLICT{} DO {}", target, action))
     }
 
 
@@ -96,31 +209,83 @@ where
         &self,
         stmt: &mut rusqlite::Statement<'_>,
         params: &Table,
-    ) -> rusqlite::Result<()>
+    ) -> crate::Result<()>
     where
         Table: Params<BindIndex = &'static str>,
     {
-        // 开启自增时，不需要发送自增的列
-        if self.with_auto_increment {
-            let table_info = T::table_info();
-            let auto_increment_columns: Vec<_> = table_info
-                .columns
-                .iter()
-                .filter(|column| column.extra.is_auto_increment)
-                .collect();
-            let params = params.params().filter(|(index, _)| {
-                // 只发送非自增列
-                !auto_increment_columns
-                    .iter()
-                    // 这里要去掉一个`:`
-                    .any(|column| column.field_name == (&index[1..]))
-            });
-            bind_params(params, stmt)?;
-        } else {
-            bind_params(params.params(), stmt)?;
-        }
+        bind_insert_params::<T, _>(self.omitted_columns, stmt, params)
+    }
+}
+
+/// The columns an insert sends
+fn insert_columns<T>(omitted_columns: OmittedColumns) -> impl Iterator<Item = &'static SqlColumnInfo>
+where
+    T: SqlTable,
+{
+    T::table_info()
+        .columns
+        .iter()
+        .copied()
+        .filter(move |column| !omitted_columns.omits(column))
+}
+
+pub(crate) fn bind_insert_params<T, Table>(
+    omitted_columns: OmittedColumns,
+    stmt: &mut rusqlite::Statement<'_>,
+    params: &Table,
+) -> crate::Result<()>
+where
+    T: SqlTable,
+    Table: Params<BindIndex = &'static str>,
+{
+    bind_column_params(
+        T::table_info().table_name,
+        insert_columns::<T>(omitted_columns),
+        stmt,
+        params,
+    )
+}
 
-        Ok(())
+impl<T> InsertRowBuilder<T>
+where
+    T: Params<BindIndex = &'static str> + SqlTable,
+{
+    /// Insert all `rows` with one prepared statement inside one transaction,
+    /// return the number of inserted rows
+    pub fn insert_rows(
+        &self,
+        connection: &rusqlite::Connection,
+        rows: &[T],
+    ) -> crate::Result<usize> {
+        self.insert_iter(connection, rows)
+    }
+
+    /// Insert all `rows` with one prepared statement inside one transaction,
+    /// return the number of inserted rows
+    /// # details
+    /// - a savepoint is used, so it also works inside an outer transaction
+    /// - nothing is inserted if any row fails
+    pub fn insert_iter<'a, I>(
+        &self,
+        connection: &rusqlite::Connection,
+        rows: I,
+    ) -> crate::Result<usize>
+    where
+        I: IntoIterator<Item = &'a T>,
+        T: 'a,
+    {
+        let context = SqlContext::new(self.build_sql()?, Some(T::table_info()));
+
+        savepoint(connection, |connection| {
+            let mut stmt = context.prepare(connection)?;
+            let mut inserted = 0;
+            for row in rows {
+                self.bind_insert_params(&mut stmt, row)
+                    .map_err(|err| context.error(err))?;
+                inserted += stmt.raw_execute().map_err(|err| context.error(err))?;
+            }
+            Ok(inserted)
+        })
     }
 }
 
@@ -134,22 +299,29 @@ where
         &self,
         connection: &'connection rusqlite::Connection,
         params: &T,
-    ) -> rusqlite::Result<Self::Executor<'connection>> {
-        let sql = self.build_sql();
+    ) -> crate::Result<Self::Executor<'connection>> {
+        let context = SqlContext::new(self.build_sql()?, Some(T::table_info()));
 
-        let mut stmt = connection.prepare(&sql)?;
+        let mut stmt = context.prepare(connection)?;
 
-        self.bind_insert_params(&mut stmt, params)?;
+        self.bind_insert_params(&mut stmt, params)
+            .map_err(|err| context.error(err))?;
 
         Ok(InsertRowExecutor {
+            connection,
             stmt,
+            context,
+            omitted_columns: self.omitted_columns,
             _marker: PhantomData,
         })
     }
 }
 
 pub struct InsertRowExecutor<'conn, T> {
+    connection: &'conn rusqlite::Connection,
     stmt: rusqlite::Statement<'conn>,
+    context: SqlContext,
+    omitted_columns: OmittedColumns,
     _marker: PhantomData<T>,
 }
 
@@ -157,14 +329,120 @@ impl<'conn, T> Executor for InsertRowExecutor<'conn, T>
 where
     T: SqlTable,
 {
-    type Output = ();
+    type Output = ExecuteResult;
+
+    fn execute(&mut self) -> crate::Result<Self::Output> {
+        let rows_affected = self
+            .stmt
+            .raw_execute()
+            .map_err(|err| self.context.error(err))?;
+        Ok(ExecuteResult::new(self.connection, rows_affected))
+    }
+
+    fn sql(&mut self) -> String {
+        self.stmt
+            .expanded_sql()
+            .unwrap_or_else(|| self.context.sql().to_string())
+    }
+}
+
+impl<'conn, T> ExecuteWith<T> for InsertRowExecutor<'conn, T>
+where
+    T: Params<BindIndex = &'static str> + SqlTable,
+{
+    fn execute_with(&mut self, params: &T) -> crate::Result<Self::Output> {
+        bind_insert_params::<T, _>(self.omitted_columns, &mut self.stmt, params)
+            .map_err(|err| self.context.error(err))?;
+        self.execute()
+    }
+}
+
+/// Building with `&mut T` writes the generated key back into the auto-increment field of `T`
+/// # details
+/// - the key is read with `RETURNING`, so an upsert that updates the existing row also writes back
+/// - the field is left untouched when no row is inserted, e.g. with `Conflict::Ignore`
+impl<T> BuilderMut<T> for InsertRowBuilder<T>
+where
+    T: Params<BindIndex = &'static str> + SqlTable,
+{
+    type Executor<'connection, 'params>
+        = InsertRowWriteBackExecutor<'connection, 'params, T>
+    where
+        T: 'params;
+
+    fn build_mut<'connection, 'params>(
+        &self,
+        connection: &'connection rusqlite::Connection,
+        params: &'params mut T,
+    ) -> crate::Result<Self::Executor<'connection, 'params>> {
+        // 自增列只能是唯一的主键列
+        let auto_increment_column = T::primary_key_columns()
+            .iter()
+            .copied()
+            .find(|column| column.extra.is_auto_increment);
+
+        let sql = match auto_increment_column {
+            Some(column) => format!("{} RETURNING {}", self.build_sql()?, column.column_name),
+            None => self.build_sql()?,
+        };
+
+        let context = SqlContext::new(sql, Some(T::table_info()));
+
+        let mut stmt = context.prepare(connection)?;
+
+        self.bind_insert_params(&mut stmt, params)
+            .map_err(|err| context.error(err))?;
+
+        Ok(InsertRowWriteBackExecutor {
+            connection,
+            stmt,
+            context,
+            row: params,
+            auto_increment_column,
+        })
+    }
+}
+
+pub struct InsertRowWriteBackExecutor<'conn, 'row, T> {
+    connection: &'conn rusqlite::Connection,
+    stmt: rusqlite::Statement<'conn>,
+    context: SqlContext,
+    row: &'row mut T,
+    auto_increment_column: Option<&'static SqlColumnInfo>,
+}
+
+impl<'conn, 'row, T> Executor for InsertRowWriteBackExecutor<'conn, 'row, T>
+where
+    T: SqlTable,
+{
+    type Output = ExecuteResult;
+
+    fn execute(&mut self) -> crate::Result<Self::Output> {
+        let Some(column) = self.auto_increment_column else {
+            let rows_affected = self
+                .stmt
+                .raw_execute()
+                .map_err(|err| self.context.error(err))?;
+            return Ok(ExecuteResult::new(self.connection, rows_affected));
+        };
+
+        let mut rows = self.stmt.raw_query();
+        // 没有插入行时 (例如 `OR IGNORE`) 不回写
+        let rows_affected = match rows.next().map_err(|err| self.context.error(err))? {
+            Some(row) => {
+                let rowid: i64 = self.context.decode(row)?;
+                set_rowid_value(self.row, column, rowid).map_err(|err| self.context.error(err))?;
+                1
+            }
+            None => 0,
+        };
 
-    fn execute(&mut self) -> rusqlite::Result<Self::Output> {
-        self.stmt.raw_execute()?;
-        Ok(())
+        Ok(ExecuteResult::new(self.connection, rows_affected))
     }
 
     fn sql(&mut self) -> String {
-        self.stmt.expanded_sql().unwrap()
+        self.stmt
+            .expanded_sql()
+            .unwrap_or_else(|| self.context.sql().to_string())
     }
 }
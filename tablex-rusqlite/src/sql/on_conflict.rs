@@ -0,0 +1,48 @@
+use crate::{Columns, SqlTable, meta::SqlColumnInfo, sql::InsertRowBuilder};
+
+pub(crate) struct Upsert {
+    pub(crate) target: Vec<&'static SqlColumnInfo>,
+    pub(crate) action: UpsertAction,
+}
+
+pub(crate) enum UpsertAction {
+    Nothing,
+    Update(Vec<&'static SqlColumnInfo>),
+}
+
+/// The `ON CONFLICT (columns)` clause of an insert, finished by `do_update` or `do_nothing`
+pub struct OnConflictBuilder<Table> {
+    insert_row_builder: InsertRowBuilder<Table>,
+    target: Vec<&'static SqlColumnInfo>,
+}
+
+impl<Table> OnConflictBuilder<Table>
+where
+    Table: SqlTable,
+{
+    pub(crate) fn new(
+        insert_row_builder: InsertRowBuilder<Table>,
+        target: Vec<&'static SqlColumnInfo>,
+    ) -> Self {
+        OnConflictBuilder {
+            insert_row_builder,
+            target,
+        }
+    }
+
+    /// Update `columns` of the existing row with the values of the inserting row
+    pub fn do_update(self, columns: impl Columns) -> InsertRowBuilder<Table> {
+        self.insert_row_builder.upsert(Upsert {
+            target: self.target,
+            action: UpsertAction::Update(columns.into_columns()),
+        })
+    }
+
+    /// Keep the existing row and skip the insert
+    pub fn do_nothing(self) -> InsertRowBuilder<Table> {
+        self.insert_row_builder.upsert(Upsert {
+            target: self.target,
+            action: UpsertAction::Nothing,
+        })
+    }
+}
@@ -1,6 +1,10 @@
 use std::marker::PhantomData;
 
-use crate::{Builder, Executor, FromRow, Params, SqlTable, sql::InsertRowBuilder};
+use crate::{
+    Builder, Error, ExecuteWith, Executor, FromRow, Params, SqlTable,
+    error::SqlContext,
+    sql::{InsertRowBuilder, OmittedColumns, bind_insert_params},
+};
 
 pub struct ReturningRowBuilder<Table> {
     insert_row_builder: InsertRowBuilder<Table>,
@@ -18,10 +22,18 @@ where
         }
     }
 
-    fn build_sql(&self) -> String {
-        let insert_sql = self.insert_row_builder.build_sql();
+    fn build_sql(&self) -> crate::Result<String> {
+        // 冲突时跳过的插入没有返回的行
+        if self.insert_row_builder.skips_conflicts() {
+            return Err(Error::Misuse(format!(
+                "`returning_row` of table `{}` can not skip conflicts, a skipped insert returns no row",
+                Table::table_info().table_name
+            )));
+        }
+
+        let insert_sql = self.insert_row_builder.build_sql()?;
 
-        format!("{} RETURNING *", insert_sql)
+        Ok(format!("{} RETURNING *", insert_sql))
     }
 }
 
@@ -35,16 +47,19 @@ where
         &self,
         connection: &'connection rusqlite::Connection,
         params: &Table,
-    ) -> rusqlite::Result<Self::Executor<'connection>> {
-        let sql = self.build_sql();
+    ) -> crate::Result<Self::Executor<'connection>> {
+        let context = SqlContext::new(self.build_sql()?, Some(Table::table_info()));
 
-        let mut stmt = connection.prepare(&sql)?;
+        let mut stmt = context.prepare(connection)?;
 
         self.insert_row_builder
-            .bind_insert_params(&mut stmt, params)?;
+            .bind_insert_params(&mut stmt, params)
+            .map_err(|err| context.error(err))?;
 
         Ok(ReturningRowExecutor {
             stmt,
+            context,
+            omitted_columns: self.insert_row_builder.omitted_columns,
             _marker: PhantomData,
         })
     }
@@ -52,6 +67,8 @@ where
 
 pub struct ReturningRowExecutor<'connection, Table> {
     stmt: rusqlite::Statement<'connection>,
+    context: SqlContext,
+    omitted_columns: OmittedColumns,
     _marker: std::marker::PhantomData<Table>,
 }
 
@@ -61,22 +78,39 @@ where
 {
     type Output = Table;
 
-    fn execute(&mut self) -> rusqlite::Result<Self::Output> {
+    fn execute(&mut self) -> crate::Result<Self::Output> {
+        let context = &self.context;
         let mut rows = self.stmt.raw_query();
 
         let table = {
-            let row = rows.next()?.ok_or(rusqlite::Error::QueryReturnedNoRows)?;
-            Table::from_row(row)?
+            let row = rows
+                .next()
+                .map_err(|err| context.error(err))?
+                .ok_or_else(|| context.error(rusqlite::Error::QueryReturnedNoRows))?;
+            context.decode(row)?
         };
 
-        if let Some(_) = rows.next()? {
-            return Err(rusqlite::Error::QueryReturnedMoreThanOneRow);
+        if rows.next().map_err(|err| context.error(err))?.is_some() {
+            return Err(context.error(rusqlite::Error::QueryReturnedMoreThanOneRow));
         }
 
         Ok(table)
     }
 
     fn sql(&mut self) -> String {
-        self.stmt.expanded_sql().unwrap()
+        self.stmt
+            .expanded_sql()
+            .unwrap_or_else(|| self.context.sql().to_string())
+    }
+}
+
+impl<Table> ExecuteWith<Table> for ReturningRowExecutor<'_, Table>
+where
+    Table: SqlTable + Params<BindIndex = &'static str> + FromRow,
+{
+    fn execute_with(&mut self, params: &Table) -> crate::Result<Self::Output> {
+        bind_insert_params::<Table, _>(self.omitted_columns, &mut self.stmt, params)
+            .map_err(|err| self.context.error(err))?;
+        self.execute()
     }
 }
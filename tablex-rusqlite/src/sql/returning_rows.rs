@@ -0,0 +1,80 @@
+use std::marker::PhantomData;
+
+use crate::{Builder, Executor, FromRow, SqlTable, error::SqlContext, sql::DeleteRowsBuilder};
+
+pub struct ReturningRowsBuilder<Table> {
+    delete_rows_builder: DeleteRowsBuilder<Table>,
+    _marker: PhantomData<Table>,
+}
+
+impl<Table> ReturningRowsBuilder<Table>
+where
+    Table: SqlTable,
+{
+    pub(crate) fn new(delete_rows_builder: DeleteRowsBuilder<Table>) -> Self {
+        ReturningRowsBuilder {
+            delete_rows_builder,
+            _marker: PhantomData,
+        }
+    }
+
+    fn build_sql(&self) -> crate::Result<String> {
+        let delete_sql = self.delete_rows_builder.build_sql()?;
+
+        Ok(format!("{} RETURNING *", delete_sql))
+    }
+}
+
+impl<Table> Builder<()> for ReturningRowsBuilder<Table>
+where
+    Table: SqlTable + FromRow,
+{
+    type Executor<'connection> = ReturningRowsExecutor<'connection, Table>;
+
+    fn build<'connection>(
+        &self,
+        connection: &'connection rusqlite::Connection,
+        _params: &(),
+    ) -> crate::Result<Self::Executor<'connection>> {
+        let context = SqlContext::new(self.build_sql()?, Some(Table::table_info()));
+
+        let mut stmt = context.prepare(connection)?;
+
+        self.delete_rows_builder
+            .bind_delete_params(&mut stmt)
+            .map_err(|err| context.error(err))?;
+
+        Ok(ReturningRowsExecutor {
+            stmt,
+            context,
+            _marker: PhantomData,
+        })
+    }
+}
+
+pub struct ReturningRowsExecutor<'connection, Table> {
+    stmt: rusqlite::Statement<'connection>,
+    context: SqlContext,
+    _marker: PhantomData<Table>,
+}
+
+impl<Table> Executor for ReturningRowsExecutor<'_, Table>
+where
+    Table: SqlTable + FromRow,
+{
+    type Output = Vec<Table>;
+
+    fn execute(&mut self) -> crate::Result<Self::Output> {
+        let context = &self.context;
+        let rows = self.stmt.raw_query().and_then(|row| context.decode(row));
+
+        rows.map(|row| row.map_err(|err| context.error(err)))
+            .collect::<crate::Result<Vec<_>>>()
+    }
+
+    fn sql(&mut self) -> String {
+        self.stmt
+            .expanded_sql()
+            .unwrap_or_else(|| self.context.sql().to_string())
+    }
+}
@@ -0,0 +1,150 @@
+use std::marker::PhantomData;
+
+use rusqlite::types::Value;
+
+use crate::{
+    Builder, Error, ExecuteWith, Executor, FromRow, Params, SqlTable, bind_user_params,
+    error::SqlContext, meta::SqlColumnInfo, sql::SelectRowsBuilder, value::owned_column_value,
+};
+
+/// The position after the last row of a page, pass it to `SelectRowsBuilder::after_cursor`
+/// to select the next page
+#[derive(Debug, Clone, PartialEq)]
+pub struct Cursor {
+    values: Vec<Value>,
+}
+
+impl Cursor {
+    pub fn new(values: Vec<Value>) -> Self {
+        Cursor { values }
+    }
+
+    /// The values of the ordered columns of the last row
+    pub fn values(&self) -> &[Value] {
+        &self.values
+    }
+
+    pub fn into_values(self) -> Vec<Value> {
+        self.values
+    }
+}
+
+/// A page of rows
+#[derive(Debug)]
+pub struct Page<Table> {
+    pub rows: Vec<Table>,
+    /// The cursor to the next page, `None` when this is the last page
+    pub next: Option<Cursor>,
+}
+
+pub struct SelectPageBuilder<Table> {
+    select_rows_builder: SelectRowsBuilder<Table>,
+    page_size: u64,
+    _marker: PhantomData<Table>,
+}
+
+impl<Table> SelectPageBuilder<Table>
+where
+    Table: SqlTable,
+{
+    pub(crate) fn new(select_rows_builder: SelectRowsBuilder<Table>, page_size: u64) -> Self {
+        SelectPageBuilder {
+            select_rows_builder,
+            page_size,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<Table, P> Builder<P> for SelectPageBuilder<Table>
+where
+    Table: SqlTable + FromRow,
+    P: Params,
+{
+    type Executor<'connection> = SelectPageExecutor<'connection, Table>;
+
+    fn build<'connection>(
+        &self,
+        connection: &'connection rusqlite::Connection,
+        params: &P,
+    ) -> crate::Result<Self::Executor<'connection>> {
+        let order_columns = self.select_rows_builder.order_columns();
+        if order_columns.is_empty() {
+            return Err(Error::Misuse(
+                "keyset pagination requires at least one `order_by` column".to_string(),
+            ));
+        }
+
+        let (stmt, context) = self.select_rows_builder.prepare(connection, params)?;
+
+        Ok(SelectPageExecutor {
+            stmt,
+            context,
+            order_columns,
+            page_size: self.page_size,
+            _marker: PhantomData,
+        })
+    }
+}
+
+pub struct SelectPageExecutor<'connection, Table> {
+    stmt: rusqlite::Statement<'connection>,
+    context: SqlContext,
+    order_columns: Vec<&'static SqlColumnInfo>,
+    page_size: u64,
+    _marker: PhantomData<Table>,
+}
+
+impl<Table> Executor for SelectPageExecutor<'_, Table>
+where
+    Table: SqlTable + FromRow,
+{
+    type Output = Page<Table>;
+
+    fn execute(&mut self) -> crate::Result<Self::Output> {
+        let context = &self.context;
+        let rows = self
+            .stmt
+            .raw_query()
+            .and_then(|row| context.decode(row))
+            .map(|row| row.map_err(|err| context.error(err)))
+            .collect::<crate::Result<Vec<Table>>>()?;
+
+        // 不满一页时说明已经是最后一页
+        let next = match rows.last() {
+            Some(last_row) if rows.len() as u64 == self.page_size => {
+                let values = self
+                    .order_columns
+                    .iter()
+                    .map(|column| {
+                        owned_column_value(last_row, column).ok_or(Error::UnreadableValue {
+                            table: Table::table_info().table_name,
+                            column,
+                        })
+                    })
+                    .collect::<crate::Result<Vec<_>>>()?;
+                Some(Cursor::new(values))
+            }
+            _ => None,
+        };
+
+        Ok(Page { rows, next })
+    }
+
+    fn sql(&mut self) -> String {
+        self.stmt
+            .expanded_sql()
+            .unwrap_or_else(|| self.context.sql().to_string())
+    }
+}
+
+impl<Table, P> ExecuteWith<P> for SelectPageExecutor<'_, Table>
+where
+    Table: SqlTable + FromRow,
+    P: Params,
+{
+    fn execute_with(&mut self, params: &P) -> crate::Result<Self::Output> {
+        bind_user_params(params.params(), &mut self.stmt).map_err(|err| self.context.error(err))?;
+        self.execute()
+    }
+}
@@ -1,12 +1,36 @@
 use std::marker::PhantomData;
 
-use rusqlite::fallible_iterator::FallibleIterator;
+use rusqlite::{
+    fallible_iterator::{self, FallibleIterator},
+    types::Value,
+};
 
-use crate::{Builder, Executor, FromRow, SqlTable};
+use crate::{
+    Builder, Columns, Error, ExecuteWith, Executor, Expr, FromRow, Order, Params, SqlTable,
+    bind_user_params,
+    error::SqlContext,
+    meta::SqlColumnInfo,
+    sql::{Cursor, Filter, SelectPageBuilder},
+    value::owned_column_value,
+};
 
-pub struct SelectRowsBuilder<Table> {
-    raw_where_clause: Option<String>,
-    _marker: std::marker::PhantomData<Table>,
+/// Select rows of `Table`, decoded into `Row` (the table itself unless `columns` is used)
+pub struct SelectRowsBuilder<Table, Row = Table> {
+    columns: Option<Vec<&'static SqlColumnInfo>>,
+    filter: Option<Filter>,
+    order_by: Vec<(&'static SqlColumnInfo, Order)>,
+    limit: Option<u64>,
+    offset: Option<u64>,
+    after: Option<After>,
+    _marker: std::marker::PhantomData<(Table, Row)>,
+}
+
+/// The position the selected rows start after
+enum After {
+    /// The values of every column of a row, the ordered ones are picked when building
+    Row(Vec<(&'static SqlColumnInfo, Option<Value>)>),
+    /// The values of the ordered columns, returned by a previous page
+    Cursor(Vec<Value>),
 }
 
 impl<Table> SelectRowsBuilder<Table>
@@ -15,81 +39,379 @@ where
 {
     pub(crate) fn new() -> Self {
         SelectRowsBuilder {
-            raw_where_clause: None,
+            columns: None,
+            filter: None,
+            order_by: Vec::new(),
+            limit: None,
+            offset: None,
+            after: None,
+            _marker: std::marker::PhantomData,
+        }
+    }
+
+    /// Select a page of at most `page_size` rows, with a cursor to the next page
+    /// # details
+    /// - the ordered columns should not be NULL, the page after a NULL can not be selected
+    pub fn page(mut self, page_size: u64) -> SelectPageBuilder<Table> {
+        self.limit = Some(page_size);
+        SelectPageBuilder::new(self, page_size)
+    }
+}
+
+impl<Table, Row> SelectRowsBuilder<Table, Row>
+where
+    Table: SqlTable,
+{
+    /// Only select `columns`, in the given order, and decode them into `NewRow`
+    /// e.g. `.columns::<(u32, String), _>((UserInfo::column_id(), UserInfo::column_name()))`
+    pub fn columns<NewRow, C>(self, columns: C) -> SelectRowsBuilder<Table, NewRow>
+    where
+        NewRow: FromRow,
+        C: Columns,
+    {
+        SelectRowsBuilder {
+            columns: Some(columns.into_columns()),
+            filter: self.filter,
+            order_by: self.order_by,
+            limit: self.limit,
+            offset: self.offset,
+            after: self.after,
             _marker: std::marker::PhantomData,
         }
     }
 
     pub fn filter_raw(mut self, raw_where_clause: impl Into<String>) -> Self {
-        self.raw_where_clause = Some(raw_where_clause.into());
+        self.filter = Some(Filter::Raw(raw_where_clause.into()));
+        self
+    }
+
+    pub fn filter(mut self, expr: Expr) -> Self {
+        self.filter = Some(Filter::Expr(expr));
+        self
+    }
+
+    /// Append `column` to the `ORDER BY` clause
+    pub fn order_by(mut self, column: &'static SqlColumnInfo, order: Order) -> Self {
+        self.order_by.push((column, order));
+        self
+    }
+
+    pub fn limit(mut self, limit: u64) -> Self {
+        self.limit = Some(limit);
+        self
+    }
+
+    pub fn offset(mut self, offset: u64) -> Self {
+        self.offset = Some(offset);
         self
     }
+
+    /// Only select the rows ordered after `last_row` (keyset pagination)
+    /// # details
+    /// - the values of the ordered columns are read when building, so `order_by` can be called later
+    /// - the ordered columns should identify a row uniquely, e.g. end with the primary key
+    /// - the ordered values of `last_row` must not be NULL, building fails with `Misuse` otherwise
+    pub fn after(mut self, last_row: &Table) -> Self {
+        let values = Table::table_info()
+            .columns
+            .iter()
+            .map(|column| (*column, owned_column_value(last_row, column)))
+            .collect();
+        self.after = Some(After::Row(values));
+        self
+    }
+
+    /// Only select the rows ordered after the `cursor` returned by a previous page
+    /// # details
+    /// - the values of the cursor must not be NULL, building fails with `Misuse` otherwise
+    pub fn after_cursor(mut self, cursor: Cursor) -> Self {
+        self.after = Some(After::Cursor(cursor.into_values()));
+        self
+    }
+
+    pub(crate) fn order_columns(&self) -> Vec<&'static SqlColumnInfo> {
+        self.order_by.iter().map(|(column, _)| *column).collect()
+    }
 }
 
-impl<Table> SelectRowsBuilder<Table>
+impl<Table, Row> SelectRowsBuilder<Table, Row>
 where
     Table: SqlTable,
 {
-    fn build_sql(&self) -> String {
+    fn build_sql(&self) -> crate::Result<String> {
         let table_info = Table::table_info();
         let table_name = table_info.table_name;
 
-        if let Some(raw_where_clause) = &self.raw_where_clause {
-            format!(
-                "SELECT * FROM {} WHERE {}",
-                table_name, raw_where_clause
-            )
-        } else {
-            format!("SELECT * FROM {}", table_name)
+        let selected_columns = self.columns.iter().flatten();
+        let ordered_columns = self.order_by.iter().map(|(column, _)| column);
+        for column in selected_columns.chain(ordered_columns) {
+            if !table_info.has_column(column) {
+                return Err(Error::UnknownColumn {
+                    table: table_name,
+                    column,
+                });
+            }
+        }
+
+        let mut conditions = Vec::new();
+        if let Some(filter) = &self.filter {
+            conditions.push(format!("({})", filter.build_sql::<Table>()?));
+        }
+        if self.after.is_some() {
+            conditions.push(self.build_after_sql()?);
+        }
+
+        let columns = match &self.columns {
+            Some(columns) => columns
+                .iter()
+                .map(|column| column.column_name)
+                .collect::<Vec<_>>()
+                .join(", "),
+            None => "*".to_string(),
+        };
+
+        let mut sql = format!("SELECT {} FROM {}", columns, table_name);
+
+        if !conditions.is_empty() {
+            sql.push_str(&format!(" WHERE {}", conditions.join(" AND ")));
+        }
+
+        if !self.order_by.is_empty() {
+            let order_by = self
+                .order_by
+                .iter()
+                .map(|(column, order)| format!("{} {}", column.column_name, order))
+                .collect::<Vec<_>>();
+            sql.push_str(&format!(" ORDER BY {}", order_by.join(", ")));
+        }
+
+        match (self.limit, self.offset) {
+            (Some(limit), Some(offset)) => {
+                sql.push_str(&format!(" LIMIT {} OFFSET {}", limit, offset))
+            }
+            (Some(limit), None) => sql.push_str(&format!(" LIMIT {}", limit)),
+            // 没有 LIMIT 时 OFFSET 需要配合 LIMIT -1 使用
+            (None, Some(offset)) => sql.push_str(&format!(" LIMIT -1 OFFSET {}", offset)),
+            (None, None) => {}
+        }
+
+        Ok(sql)
+    }
+
+    /// Build the keyset condition
+    /// `(a > :a) OR (a = :a AND b > :b) OR ...`, `<` for descending columns
+    fn build_after_sql(&self) -> crate::Result<String> {
+        if self.order_by.is_empty() {
+            return Err(Error::Misuse(
+                "keyset pagination requires at least one `order_by` column".to_string(),
+            ));
         }
+
+        let conditions = (0..self.order_by.len())
+            .map(|index| {
+                let mut terms = self.order_by[..index]
+                    .iter()
+                    .enumerate()
+                    .map(|(previous, (column, _))| {
+                        format!("{} = :__tablex_after_{}", column.column_name, previous + 1)
+                    })
+                    .collect::<Vec<_>>();
+
+                let (column, order) = &self.order_by[index];
+                let operator = match order {
+                    Order::Asc => ">",
+                    Order::Desc => "<",
+                };
+                terms.push(format!(
+                    "{} {} :__tablex_after_{}",
+                    column.column_name,
+                    operator,
+                    index + 1
+                ));
+
+                format!("({})", terms.join(" AND "))
+            })
+            .collect::<Vec<_>>();
+
+        Ok(format!("({})", conditions.join(" OR ")))
+    }
+
+    /// The values of the ordered columns to start after
+    fn after_values(&self) -> crate::Result<Vec<&Value>> {
+        let table_name = Table::table_info().table_name;
+
+        let values = match &self.after {
+            None => return Ok(Vec::new()),
+            // 在构建时按最终的排序列取值
+            Some(After::Row(row_values)) => self
+                .order_by
+                .iter()
+                .map(|(column, _)| {
+                    row_values
+                        .iter()
+                        .find(|(row_column, _)| std::ptr::eq(*row_column, *column))
+                        .and_then(|(_, value)| value.as_ref())
+                        // 排序列的类型不是内置的 SqlType 时无法读取
+                        .ok_or(Error::UnreadableValue {
+                            table: table_name,
+                            column,
+                        })
+                })
+                .collect::<crate::Result<Vec<_>>>()?,
+            Some(After::Cursor(cursor_values)) => {
+                if cursor_values.len() != self.order_by.len() {
+                    return Err(Error::Misuse(format!(
+                        "the cursor has {} values but {} columns are ordered",
+                        cursor_values.len(),
+                        self.order_by.len()
+                    )));
+                }
+                cursor_values.iter().collect()
+            }
+        };
+
+        // `column > NULL` 不匹配任何行, 分页会提前结束
+        for (value, (column, _)) in values.iter().zip(&self.order_by) {
+            if **value == Value::Null {
+                return Err(Error::Misuse(format!(
+                    "keyset pagination can not start after NULL in column `{}` of table `{}`",
+                    column.column_name, table_name
+                )));
+            }
+        }
+
+        Ok(values)
+    }
+
+    fn bind_after_values(&self, stmt: &mut rusqlite::Statement<'_>) -> crate::Result<()> {
+        for (index, value) in self.after_values()?.into_iter().enumerate() {
+            let name = format!(":__tablex_after_{}", index + 1);
+            stmt.raw_bind_parameter(name.as_str(), value)?;
+        }
+
+        Ok(())
+    }
+
+    pub(crate) fn prepare<'connection, P>(
+        &self,
+        connection: &'connection rusqlite::Connection,
+        params: &P,
+    ) -> crate::Result<(rusqlite::Statement<'connection>, SqlContext)>
+    where
+        P: Params,
+    {
+        let context = SqlContext::new(self.build_sql()?, Some(Table::table_info()));
+
+        let mut stmt = context.prepare(connection)?;
+
+        bind_user_params(params.params(), &mut stmt).map_err(|err| context.error(err))?;
+
+        if let Some(filter) = &self.filter {
+            filter
+                .bind_values(&mut stmt)
+                .map_err(|err| context.error(err))?;
+        }
+
+        self.bind_after_values(&mut stmt)
+            .map_err(|err| context.error(err))?;
+
+        Ok((stmt, context))
     }
 }
 
-impl<Table> Builder<()> for SelectRowsBuilder<Table>
+/// `params` are bound to the placeholders of `filter_raw`, e.g. `filter_raw("age > ?1")` with `&(40,)`
+impl<Table, Row, P> Builder<P> for SelectRowsBuilder<Table, Row>
 where
-    Table: SqlTable + FromRow,
+    Table: SqlTable,
+    Row: FromRow,
+    P: Params,
 {
-    type Executor<'connection> = SelectRowsExecutor<'connection, Table>;
+    type Executor<'connection> = SelectRowsExecutor<'connection, Row>;
 
     fn build<'connection>(
         &self,
         connection: &'connection rusqlite::Connection,
-        _params: &(),
-    ) -> rusqlite::Result<Self::Executor<'connection>> {
-        let sql = self.build_sql();
-
-        let stmt = connection.prepare(&sql)?;
+        params: &P,
+    ) -> crate::Result<Self::Executor<'connection>> {
+        let (stmt, context) = self.prepare(connection, params)?;
 
         Ok(SelectRowsExecutor {
             stmt,
-            _marker: PhantomData::default(),
+            context,
+            _marker: PhantomData,
         })
     }
 }
 
 pub struct SelectRowsExecutor<'connection, Table> {
     stmt: rusqlite::Statement<'connection>,
+    context: SqlContext,
     _marker: PhantomData<Table>,
 }
 
-impl<'connection, Table> Executor for SelectRowsExecutor<'connection, Table> 
-where 
+impl<'connection, Table> SelectRowsExecutor<'connection, Table>
+where
     Table: FromRow,
 {
-    type Output = Vec<Table>;
+    /// Decode the rows one at a time instead of collecting them
+    pub fn execute_iter(&mut self) -> impl FallibleIterator<Item = Table, Error = Error> + '_ {
+        let context = &self.context;
+        let rows = self.stmt.raw_query().and_then(|row| context.decode(row));
+        fallible_iterator::convert(rows).map_err(|err| context.error(err))
+    }
+
+    /// Call `f` with every decoded row, stop at the first error
+    pub fn for_each<F>(&mut self, mut f: F) -> crate::Result<()>
+    where
+        F: FnMut(Table) -> crate::Result<()>,
+    {
+        let mut rows = self.stmt.raw_query();
+        while let Some(row) = rows.next().map_err(|err| self.context.error(err))? {
+            f(self.context.decode(row)?)?;
+        }
+        Ok(())
+    }
 
-    fn execute(&mut self) -> rusqlite::Result<Self::Output> {
-        let rows = self.stmt
-            .raw_query();
+    /// Decode the first row only, `QueryReturnedNoRows` if there is no row
+    pub fn first(&mut self) -> crate::Result<Table> {
+        self.optional()?
+            .ok_or_else(|| self.context.error(rusqlite::Error::QueryReturnedNoRows))
+    }
 
-        let tables = rows
-            .map(|row| Table::from_row(row))
-            .collect::<Vec<_>>();
+    /// Decode the first row only, `None` if there is no row
+    pub fn optional(&mut self) -> crate::Result<Option<Table>> {
+        let mut rows = self.stmt.raw_query();
+        match rows.next().map_err(|err| self.context.error(err))? {
+            Some(row) => self.context.decode(row).map(Some),
+            None => Ok(None),
+        }
+    }
+}
+
+impl<'connection, Table> Executor for SelectRowsExecutor<'connection, Table>
+where
+    Table: FromRow,
+{
+    type Output = Vec<Table>;
 
-        tables
+    fn execute(&mut self) -> crate::Result<Self::Output> {
+        self.execute_iter().collect::<Vec<_>>()
     }
 
     fn sql(&mut self) -> String {
-        self.stmt.expanded_sql().unwrap()
+        self.stmt
+            .expanded_sql()
+            .unwrap_or_else(|| self.context.sql().to_string())
+    }
+}
+
+impl<'connection, Table, P> ExecuteWith<P> for SelectRowsExecutor<'connection, Table>
+where
+    Table: FromRow,
+    P: Params,
+{
+    fn execute_with(&mut self, params: &P) -> crate::Result<Self::Output> {
+        bind_user_params(params.params(), &mut self.stmt).map_err(|err| self.context.error(err))?;
+        self.execute()
     }
 }
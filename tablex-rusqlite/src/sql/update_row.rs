@@ -0,0 +1,191 @@
+use std::marker::PhantomData;
+
+use crate::{
+    Builder, Conflict, Error, ExecuteResult, ExecuteWith, Executor, Expr, Params, SqlTable,
+    bind_column_params, error::SqlContext, meta::SqlColumnInfo,
+};
+
+pub struct UpdateRowBuilder<Table> {
+    conflict: Option<Conflict>,
+    filter: Option<Expr>,
+    _marker: PhantomData<Table>,
+}
+
+impl<Table> UpdateRowBuilder<Table>
+where
+    Table: SqlTable,
+{
+    pub(crate) fn new() -> Self {
+        UpdateRowBuilder {
+            conflict: None,
+            filter: None,
+            _marker: PhantomData,
+        }
+    }
+
+    pub fn or(mut self, conflict: Conflict) -> Self {
+        self.conflict = Some(conflict);
+        self
+    }
+
+    /// Only update the row when `expr` also matches, e.g. an optimistic lock on a version column
+    pub fn filter(mut self, expr: Expr) -> Self {
+        self.filter = Some(expr);
+        self
+    }
+}
+
+impl<T> UpdateRowBuilder<T>
+where
+    T: SqlTable,
+{
+    fn build_sql(&self) -> crate::Result<String> {
+        let table_info = T::table_info();
+        let table_name = table_info.table_name;
+
+        let or = self
+            .conflict
+            .as_ref()
+            .map(|conflict| format!(" OR {}", conflict))
+            .unwrap_or_default();
+
+        // 主键列用于定位行, 其余列全部更新
+        let primary_columns = T::primary_key_columns();
+        let value_columns = update_value_columns::<T>().collect::<Vec<_>>();
+
+        if primary_columns.is_empty() {
+            return Err(Error::MissingPrimaryKey { table: table_name });
+        }
+
+        // 全部是主键列 (或生成列) 时没有可以更新的列
+        if value_columns.is_empty() {
+            return Err(Error::Misuse(format!(
+                "table `{}` has no columns to update besides its primary key",
+                table_name
+            )));
+        }
+
+        let assignments = value_columns
+            .iter()
+            .map(|column| format!("{} = :{}", column.column_name, column.field_name))
+            .collect::<Vec<_>>();
+
+        let mut conditions = primary_columns
+            .iter()
+            .map(|column| format!("{} = :{}", column.column_name, column.field_name))
+            .collect::<Vec<_>>();
+
+        if let Some(filter) = &self.filter {
+            filter.check_table::<T>()?;
+            conditions.push(filter.build_sql());
+        }
+
+        Ok(format!(
+            "UPDATE{} {} SET {} WHERE {}",
+            or,
+            table_name,
+            assignments.join(", "),
+            conditions.join(" AND ")
+        ))
+    }
+}
+
+/// The columns set by the update, generated columns are computed by SQLite
+fn update_value_columns<T>() -> impl Iterator<Item = &'static SqlColumnInfo>
+where
+    T: SqlTable,
+{
+    let primary_columns = T::primary_key_columns();
+    T::table_info()
+        .columns
+        .iter()
+        .copied()
+        .filter(|column| !primary_columns.iter().any(|key| std::ptr::eq(*key, *column)))
+        .filter(|column| column.extra.generated.is_none())
+}
+
+/// Every primary key and value column is a placeholder of the update, either in `SET` or in `WHERE`
+fn bind_update_params<T>(stmt: &mut rusqlite::Statement<'_>, params: &T) -> crate::Result<()>
+where
+    T: Params<BindIndex = &'static str> + SqlTable,
+{
+    bind_column_params(
+        T::table_info().table_name,
+        T::primary_key_columns()
+            .iter()
+            .copied()
+            .chain(update_value_columns::<T>()),
+        stmt,
+        params,
+    )
+}
+
+impl<T> Builder<T> for UpdateRowBuilder<T>
+where
+    T: Params<BindIndex = &'static str> + SqlTable,
+{
+    type Executor<'connection> = UpdateRowExecutor<'connection, T>;
+
+    fn build<'connection>(
+        &self,
+        connection: &'connection rusqlite::Connection,
+        params: &T,
+    ) -> crate::Result<Self::Executor<'connection>> {
+        let context = SqlContext::new(self.build_sql()?, Some(T::table_info()));
+
+        let mut stmt = context.prepare(connection)?;
+
+        bind_update_params::<T>(&mut stmt, params).map_err(|err| context.error(err))?;
+
+        if let Some(filter) = &self.filter {
+            filter
+                .bind_values(&mut stmt)
+                .map_err(|err| context.error(err))?;
+        }
+
+        Ok(UpdateRowExecutor {
+            connection,
+            stmt,
+            context,
+            _marker: PhantomData,
+        })
+    }
+}
+
+pub struct UpdateRowExecutor<'conn, T> {
+    connection: &'conn rusqlite::Connection,
+    stmt: rusqlite::Statement<'conn>,
+    context: SqlContext,
+    _marker: PhantomData<T>,
+}
+
+impl<'conn, T> Executor for UpdateRowExecutor<'conn, T>
+where
+    T: SqlTable,
+{
+    type Output = ExecuteResult;
+
+    fn execute(&mut self) -> crate::Result<Self::Output> {
+        let rows_affected = self
+            .stmt
+            .raw_execute()
+            .map_err(|err| self.context.error(err))?;
+        Ok(ExecuteResult::new(self.connection, rows_affected))
+    }
+
+    fn sql(&mut self) -> String {
+        self.stmt
+            .expanded_sql()
+            .unwrap_or_else(|| self.context.sql().to_string())
+    }
+}
+
+impl<'conn, T> ExecuteWith<T> for UpdateRowExecutor<'conn, T>
+where
+    T: Params<BindIndex = &'static str> + SqlTable,
+{
+    fn execute_with(&mut self, params: &T) -> crate::Result<Self::Output> {
+        bind_update_params::<T>(&mut self.stmt, params).map_err(|err| self.context.error(err))?;
+        self.execute()
+    }
+}
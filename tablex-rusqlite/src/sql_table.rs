@@ -1,12 +1,16 @@
 use tablex::Table;
 
-use crate::meta::SqlExtraColumnInfo;
+use crate::meta::{SqlColumnInfo, SqlExtraColumnInfo, SqlExtraTableInfo};
 
 
 
 pub trait SqlTable:
-    Sized + Table<ExtraTableInfo = (), ExtraColumnInfo = SqlExtraColumnInfo>
+    Sized + Table<ExtraTableInfo = SqlExtraTableInfo, ExtraColumnInfo = SqlExtraColumnInfo>
 {
+    /// The columns of the primary key, in order, empty if the table has none
+    fn primary_key_columns() -> &'static [&'static SqlColumnInfo] {
+        &Self::table_info().extra.primary_key
+    }
 }
 
-impl<T> SqlTable for T where T: Table<ExtraTableInfo = (), ExtraColumnInfo = SqlExtraColumnInfo> {}
+impl<T> SqlTable for T where T: Table<ExtraTableInfo = SqlExtraTableInfo, ExtraColumnInfo = SqlExtraColumnInfo> {}
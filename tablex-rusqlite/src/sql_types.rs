@@ -1,6 +1,6 @@
 use std::num::*;
 
-use rusqlite::ToSql;
+use rusqlite::{ToSql, types::Value};
 use uuid::Uuid;
 
 /// Sql type Info 
@@ -12,6 +12,27 @@ pub trait SqlType : ToSql {
     fn is_nullable() -> bool;
 }
 
+/// A `SqlType` which can be a column of a `STRICT` table,
+/// i.e. its `type_name` is "INTEGER", "REAL", "TEXT", "BLOB" or "ANY"
+/// # details
+/// - the trait is sealed, so a `STRICT` table only accepts the built-in types (and `Option` of them),
+///   not even a user `SqlType` whose `type_name` is "TEXT" or "BLOB"
+/// - `rusqlite::types::Value` is the "ANY" column, which keeps every value as it is
+#[diagnostic::on_unimplemented(
+    message = "`{Self}` can not be a column of a STRICT table",
+    note = "only the built-in INTEGER, REAL, TEXT, BLOB and ANY (`rusqlite::types::Value`) column types can be used in a STRICT table"
+)]
+pub trait StrictSqlType: SqlType + sealed::Sealed {}
+
+mod sealed {
+    /// 防止外部类型实现 `StrictSqlType`
+    pub trait Sealed {}
+}
+
+impl<T: StrictSqlType> sealed::Sealed for Option<T> {}
+
+impl<T: StrictSqlType> StrictSqlType for Option<T> {}
+
 impl<T: SqlType> SqlType for Option<T> {
     fn type_name() -> &'static str {
         T::type_name()
@@ -31,6 +52,10 @@ macro_rules! impl_sql_type {
 
             fn is_nullable() -> bool { false }
         }
+
+        impl sealed::Sealed for $sql_type {}
+
+        impl StrictSqlType for $sql_type {}
     };
 }
 
@@ -59,4 +84,6 @@ impl_sql_type!(f64, "REAL");
 
 impl_sql_type!(String, "TEXT");
 
-impl_sql_type!(Uuid, "BLOB");
\ No newline at end of file
+impl_sql_type!(Uuid, "BLOB");
+
+impl_sql_type!(Value, "ANY");
\ No newline at end of file
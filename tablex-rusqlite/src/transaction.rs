@@ -0,0 +1,290 @@
+use std::time::Duration;
+
+use rusqlite::{Connection, ErrorCode, TransactionBehavior};
+
+use crate::Error;
+
+/// How `ConnectionExt::transaction_with` starts and retries a transaction
+#[derive(Clone)]
+pub struct TransactionOptions {
+    /// `BEGIN DEFERRED`, `BEGIN IMMEDIATE` or `BEGIN EXCLUSIVE`
+    pub behavior: TransactionBehavior,
+    /// How many times the whole transaction is retried when the database is busy
+    pub busy_retries: u32,
+    /// How long to wait before each retry
+    pub busy_retry_delay: Duration,
+}
+
+impl Default for TransactionOptions {
+    fn default() -> Self {
+        TransactionOptions {
+            behavior: TransactionBehavior::Deferred,
+            busy_retries: 3,
+            busy_retry_delay: Duration::from_millis(100),
+        }
+    }
+}
+
+impl TransactionOptions {
+    pub fn behavior(mut self, behavior: TransactionBehavior) -> Self {
+        self.behavior = behavior;
+        self
+    }
+
+    pub fn busy_retries(mut self, busy_retries: u32, busy_retry_delay: Duration) -> Self {
+        self.busy_retries = busy_retries;
+        self.busy_retry_delay = busy_retry_delay;
+        self
+    }
+}
+
+/// Roll back when dropped before `finish`, e.g. when the closure panics
+struct Guard<'connection> {
+    connection: &'connection Connection,
+    rollback_sql: &'static str,
+    finished: bool,
+}
+
+impl<'connection> Guard<'connection> {
+    fn new(connection: &'connection Connection, rollback_sql: &'static str) -> Self {
+        Guard {
+            connection,
+            rollback_sql,
+            finished: false,
+        }
+    }
+
+    fn finish(mut self, sql: &'static str) -> rusqlite::Result<()> {
+        self.finished = true;
+        self.connection.execute_batch(sql)
+    }
+
+    /// Roll back after `f` failed, the error of `f` is what the caller needs to see
+    /// # details
+    /// - nothing to roll back when SQLite already ended the transaction, e.g. `INSERT OR ROLLBACK`
+    /// - a failed rollback is ignored
+    fn rollback(mut self) {
+        self.finished = true;
+        self.try_rollback();
+    }
+
+    fn try_rollback(&self) {
+        if !self.connection.is_autocommit() {
+            let _ = self.connection.execute_batch(self.rollback_sql);
+        }
+    }
+}
+
+impl Drop for Guard<'_> {
+    fn drop(&mut self) {
+        if !self.finished {
+            self.try_rollback();
+        }
+    }
+}
+
+/// Run `f` inside a savepoint, released on `Ok` and rolled back on `Err` or panic
+pub(crate) fn savepoint<R, F>(connection: &Connection, f: F) -> crate::Result<R>
+where
+    F: FnOnce(&Connection) -> crate::Result<R>,
+{
+    const RELEASE: &str = "RELEASE tablex_savepoint";
+    const ROLLBACK: &str = "ROLLBACK TO tablex_savepoint; RELEASE tablex_savepoint";
+
+    // 同名的保存点可以嵌套, ROLLBACK TO 和 RELEASE 作用于最近的一个
+    connection.execute_batch("SAVEPOINT tablex_savepoint")?;
+    let guard = Guard::new(connection, ROLLBACK);
+
+    match f(connection) {
+        Ok(value) => {
+            guard.finish(RELEASE)?;
+            Ok(value)
+        }
+        Err(err) => {
+            guard.rollback();
+            Err(err)
+        }
+    }
+}
+
+pub(crate) fn transaction<R, F>(
+    connection: &Connection,
+    options: &TransactionOptions,
+    mut f: F,
+) -> crate::Result<R>
+where
+    F: FnMut(&Connection) -> crate::Result<R>,
+{
+    // 已经在事务中时使用保存点
+    if !connection.is_autocommit() {
+        return savepoint(connection, f);
+    }
+
+    let begin = match options.behavior {
+        TransactionBehavior::Deferred => "BEGIN DEFERRED",
+        TransactionBehavior::Immediate => "BEGIN IMMEDIATE",
+        TransactionBehavior::Exclusive => "BEGIN EXCLUSIVE",
+        _ => "BEGIN",
+    };
+
+    let mut retries = 0;
+    loop {
+        let result = connection
+            .execute_batch(begin)
+            .map_err(Error::from)
+            .and_then(|_| {
+                let guard = Guard::new(connection, "ROLLBACK");
+                match f(connection) {
+                    Ok(value) => {
+                        guard.finish("COMMIT")?;
+                        Ok(value)
+                    }
+                    Err(err) => {
+                        guard.rollback();
+                        Err(err)
+                    }
+                }
+            });
+
+        // COMMIT 失败时事务仍然是打开的, 回滚失败时保留 COMMIT 的错误
+        if result.is_err() && !connection.is_autocommit() {
+            let _ = connection.execute_batch("ROLLBACK");
+        }
+
+        match result {
+            Err(err)
+                if err.sqlite_error_code() == Some(ErrorCode::DatabaseBusy)
+                    && retries < options.busy_retries =>
+            {
+                retries += 1;
+                std::thread::sleep(options.busy_retry_delay);
+            }
+            result => return result,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::{
+        panic::{AssertUnwindSafe, catch_unwind},
+        time::Duration,
+    };
+
+    use rusqlite::{Connection, ErrorCode, TransactionBehavior};
+
+    use crate::{ConnectionExt, TransactionOptions};
+
+    fn count(connection: &Connection) -> i64 {
+        connection
+            .query_row("SELECT COUNT(*) FROM item", [], |row| row.get(0))
+            .unwrap()
+    }
+
+    #[test]
+    fn rollback() {
+        let connection = Connection::open_in_memory().unwrap();
+        connection
+            .execute_batch("CREATE TABLE item (id INTEGER UNIQUE)")
+            .unwrap();
+
+        // 出错时回滚
+        let result = connection.transaction(|tx| {
+            tx.execute_batch("INSERT INTO item VALUES (1)")?;
+            Err::<(), _>(rusqlite::Error::QueryReturnedNoRows.into())
+        });
+        assert!(result.is_err());
+        assert_eq!(count(&connection), 0);
+
+        // panic 时回滚
+        let result = catch_unwind(AssertUnwindSafe(|| {
+            connection.transaction::<(), _>(|tx| {
+                tx.execute_batch("INSERT INTO item VALUES (1)")?;
+                panic!("failed inside the transaction");
+            })
+        }));
+        assert!(result.is_err());
+        assert!(connection.is_autocommit());
+        assert_eq!(count(&connection), 0);
+
+        // 嵌套的保存点只回滚自己
+        connection
+            .transaction(|tx| {
+                tx.execute_batch("INSERT INTO item VALUES (1)")?;
+                let nested = tx.transaction(|tx| {
+                    tx.execute_batch("INSERT INTO item VALUES (2)")?;
+                    Err::<(), _>(rusqlite::Error::QueryReturnedNoRows.into())
+                });
+                assert!(nested.is_err());
+                Ok(())
+            })
+            .unwrap();
+        assert_eq!(count(&connection), 1);
+
+        // SQLite 自己回滚了事务时, 返回原来的错误
+        let err = connection
+            .transaction(|tx| {
+                tx.execute_batch("INSERT OR ROLLBACK INTO item VALUES (1)")?;
+                Ok(())
+            })
+            .unwrap_err();
+        assert_eq!(err.sqlite_error_code(), Some(ErrorCode::ConstraintViolation));
+        assert!(connection.is_autocommit());
+
+        // 嵌套的保存点中也一样
+        let err = connection
+            .transaction(|tx| {
+                tx.execute_batch("INSERT INTO item VALUES (2)")?;
+                tx.transaction(|tx| {
+                    tx.execute_batch("INSERT OR ROLLBACK INTO item VALUES (1)")?;
+                    Ok(())
+                })
+            })
+            .unwrap_err();
+        assert_eq!(err.sqlite_error_code(), Some(ErrorCode::ConstraintViolation));
+        assert!(connection.is_autocommit());
+        assert_eq!(count(&connection), 1);
+    }
+
+    #[test]
+    fn busy_retry() {
+        let path = std::env::temp_dir().join(format!("tablex_busy_{}.db", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+
+        let locker = Connection::open(&path).unwrap();
+        locker
+            .execute_batch("CREATE TABLE item (id INTEGER UNIQUE)")
+            .unwrap();
+        let connection = Connection::open(&path).unwrap();
+        // 不等待锁, 由重试处理
+        connection.busy_timeout(Duration::ZERO).unwrap();
+        let options = TransactionOptions::default().behavior(TransactionBehavior::Immediate);
+
+        // 不重试时返回 busy 错误
+        locker.execute_batch("BEGIN IMMEDIATE").unwrap();
+        let err = connection
+            .transaction_with(&options.clone().busy_retries(0, Duration::ZERO), |tx| {
+                tx.execute_batch("INSERT INTO item VALUES (1)")?;
+                Ok(())
+            })
+            .unwrap_err();
+        assert_eq!(err.sqlite_error_code(), Some(ErrorCode::DatabaseBusy));
+
+        // 锁释放后重试成功
+        let unlock = std::thread::spawn(move || {
+            std::thread::sleep(Duration::from_millis(50));
+            locker.execute_batch("COMMIT").unwrap();
+        });
+        connection
+            .transaction_with(&options.busy_retries(100, Duration::from_millis(10)), |tx| {
+                tx.execute_batch("INSERT INTO item VALUES (1)")?;
+                Ok(())
+            })
+            .unwrap();
+        unlock.join().unwrap();
+        assert_eq!(count(&connection), 1);
+
+        drop(connection);
+        let _ = std::fs::remove_file(&path);
+    }
+}
@@ -0,0 +1,92 @@
+use std::num::*;
+
+use rusqlite::{
+    ToSql,
+    types::{ToSqlOutput, Value},
+};
+use uuid::Uuid;
+
+use crate::{Error, SqlTable, meta::SqlColumnInfo};
+
+/// Read the value of `column` from `table` as a [`ToSql`]
+///
+/// The field is looked up through [`tablex::Table::value_ref`] by trying every
+/// built-in [`crate::SqlType`], `None` is returned for any other field type.
+pub(crate) fn column_value<'a, T>(table: &'a T, column: &SqlColumnInfo) -> Option<&'a dyn ToSql>
+where
+    T: SqlTable,
+{
+    macro_rules! try_types {
+        ($($ty:ty),* $(,)?) => {
+            $(
+                if let Some(value) = table.value_ref::<$ty>(column) {
+                    return Some(value as &dyn ToSql);
+                }
+                if let Some(value) = table.value_ref::<Option<$ty>>(column) {
+                    return Some(value as &dyn ToSql);
+                }
+            )*
+        };
+    }
+
+    try_types!(
+        i8, u8, i16, u16, i32, u32, i64, u64,
+        NonZeroI8, NonZeroU8, NonZeroI16, NonZeroU16,
+        NonZeroI32, NonZeroU32, NonZeroI64, NonZeroU64,
+        bool, f32, f64, String, Uuid,
+    );
+
+    None
+}
+
+/// Read the value of `column` from `table` as an owned [`Value`]
+pub(crate) fn owned_column_value<T>(table: &T, column: &SqlColumnInfo) -> Option<Value>
+where
+    T: SqlTable,
+{
+    match column_value(table, column)?.to_sql().ok()? {
+        ToSqlOutput::Borrowed(value) => Some(value.into()),
+        ToSqlOutput::Owned(value) => Some(value),
+        _ => None,
+    }
+}
+
+/// Write a rowid into the integer field of `column`
+pub(crate) fn set_rowid_value<T>(
+    table: &mut T,
+    column: &'static SqlColumnInfo,
+    rowid: i64,
+) -> crate::Result<()>
+where
+    T: SqlTable,
+{
+    let table_name = T::table_info().table_name;
+    let out_of_range = || Error::Sqlite {
+        source: rusqlite::Error::IntegralValueOutOfRange(0, rowid),
+        sql: None,
+        table: Some(table_name),
+        column: Some(column),
+    };
+
+    macro_rules! try_types {
+        ($($ty:ty),* $(,)?) => {
+            $(
+                if let Some(field) = table.value_mut::<$ty>(column) {
+                    *field = <$ty>::try_from(rowid).map_err(|_| out_of_range())?;
+                    return Ok(());
+                }
+                if let Some(field) = table.value_mut::<Option<$ty>>(column) {
+                    *field = Some(<$ty>::try_from(rowid).map_err(|_| out_of_range())?);
+                    return Ok(());
+                }
+            )*
+        };
+    }
+
+    try_types!(i8, u8, i16, u16, i32, u32, i64, u64);
+
+    Err(Error::UnreadableValue {
+        table: table_name,
+        column,
+    })
+}
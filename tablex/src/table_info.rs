@@ -23,8 +23,6 @@ where
     pub fn has_column(&self, column: &Column<ExtraColumnInfo>) -> bool {
         self.columns
             .iter()
-            .any(|c| {
-                ((*c) as *const _) == (column as *const Column<ExtraColumnInfo>)
-            })
+            .any(|c| std::ptr::eq(*c, column))
     }
 }
\ No newline at end of file